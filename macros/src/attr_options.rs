@@ -3,7 +3,7 @@ use quote::{quote, ToTokens};
 use syn::{
     parse::{Parse, Parser},
     spanned::Spanned,
-    Expr, ExprArray, Ident, Token, Type,
+    Expr, ExprArray, Ident, LitStr, Token, Type,
 };
 
 use crate::Macro;
@@ -36,6 +36,12 @@ pub(crate) struct AttrOptions {
     /// The `BenchOptions.counters` field and its value, followed by a comma.
     pub counters: proc_macro2::TokenStream,
 
+    /// `ItemsCount` expression of `__divan_arg` if `counter = arg` is set.
+    pub arg_counter: Option<proc_macro2::TokenStream>,
+
+    /// Key/value pairs from `meta(...)`.
+    pub custom_meta: Vec<(LitStr, Expr)>,
+
     /// Options used directly as `BenchOptions` fields.
     ///
     /// Option reuse is handled by the compiler ensuring `BenchOptions` fields
@@ -50,10 +56,12 @@ impl AttrOptions {
         let mut divan_crate = None::<syn::Path>;
         let mut name_expr = None::<Expr>;
         let mut args_expr = None::<Expr>;
+        let mut custom_meta = None::<Vec<(LitStr, Expr)>>;
         let mut bench_options = Vec::new();
 
         let mut counters = Vec::<(proc_macro2::TokenStream, Option<&str>)>::new();
         let mut counters_ident = None::<Ident>;
+        let mut arg_counter_span = None::<proc_macro2::Span>;
 
         let mut seen_bytes_count = false;
         let mut seen_chars_count = false;
@@ -127,11 +135,67 @@ impl AttrOptions {
 
                     parse!(args_expr);
                 }
+                "meta" => {
+                    if custom_meta.is_some() {
+                        return repeat_error();
+                    }
+
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+
+                    let pairs = content.parse_terminated(
+                        |input| {
+                            let key: LitStr = input.parse()?;
+                            input.parse::<Token![=]>()?;
+                            let value: Expr = input.parse()?;
+                            Ok((key, value))
+                        },
+                        Token![,],
+                    )?;
+
+                    let mut result = Vec::<(LitStr, Expr)>::with_capacity(pairs.len());
+                    for (key, value) in pairs {
+                        // Pairs are written as `key=value` fields in line-based
+                        // outputs, such as baselines and `--format terse`.
+                        if key.value().contains(['=', '\t', '\n', '\r']) {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                format_args!(
+                                    "key {:?} in '{macro_name}' option '{ident_name}' must not contain '=', tabs, or line breaks",
+                                    key.value()
+                                ),
+                            ));
+                        }
+
+                        if result.iter().any(|(prev, _)| prev.value() == key.value()) {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                format_args!(
+                                    "repeated key {:?} in '{macro_name}' option '{ident_name}'",
+                                    key.value()
+                                ),
+                            ));
+                        }
+                        result.push((key, value));
+                    }
+
+                    custom_meta = Some(result);
+                }
                 "counter" => {
-                    if counters_ident.is_some() {
+                    if counters_ident.is_some() || arg_counter_span.is_some() {
                         return repeat_error();
                     }
                     let value: Expr = meta.value()?.parse()?;
+
+                    // `counter = arg` counts items from the `args` value.
+                    if matches!(&value, Expr::Path(path) if path.path.is_ident("arg")) {
+                        if !matches!(target_macro, Macro::Bench { .. }) {
+                            error!("unsupported '{macro_name}' option value '{ident_name} = arg'");
+                        }
+                        arg_counter_span = Some(value.span());
+                        return Ok(());
+                    }
+
                     counters.push((value.into_token_stream(), None));
                     counters_ident = Some(Ident::new("counters", ident.span()));
                 }
@@ -195,6 +259,14 @@ impl AttrOptions {
             Err(error) => return Err(error.into_compile_error().into()),
         }
 
+        if let (Some(span), None) = (arg_counter_span, &args_expr) {
+            let error = syn::Error::new(
+                span,
+                format_args!("'args' required for '{macro_name}' option 'counter = arg'"),
+            );
+            return Err(error.into_compile_error().into());
+        }
+
         let divan_crate = divan_crate.unwrap_or_else(|| syn::parse_quote!(::divan));
         let private_mod = quote! { #divan_crate::__private };
         let std_crate = quote! { #private_mod::std };
@@ -215,6 +287,16 @@ impl AttrOptions {
             None => expr.to_token_stream(),
         });
 
+        let arg_counter = arg_counter_span.map(|_| {
+            quote! {
+                {
+                    use #std_crate::convert::From as _;
+
+                    #divan_crate::counter::ItemsCount::from(__divan_arg)
+                }
+            }
+        });
+
         let counters = counters_ident
             .map(|ident| {
                 quote! {
@@ -223,7 +305,17 @@ impl AttrOptions {
             })
             .unwrap_or_default();
 
-        Ok(Self { std_crate, private_mod, name_expr, args_expr, generic, counters, bench_options })
+        Ok(Self {
+            std_crate,
+            private_mod,
+            name_expr,
+            args_expr,
+            generic,
+            counters,
+            arg_counter,
+            custom_meta: custom_meta.unwrap_or_default(),
+            bench_options,
+        })
     }
 
     /// Produces a function expression for creating `BenchOptions`.
@@ -279,7 +371,7 @@ impl AttrOptions {
 
                     // If the option is a `Duration`, use `IntoDuration` to be
                     // polymorphic over `Duration` or `u64`/`f64` seconds.
-                    "min_time" | "max_time" => {
+                    "min_time" | "max_time" | "max_sample_time" | "warm_up_time" => {
                         wrapped_value =
                             quote! { #private_mod::IntoDuration::into_duration(#value) };
                         &wrapped_value
@@ -203,6 +203,13 @@ pub fn bench(options: TokenStream, item: TokenStream) -> TokenStream {
         })
         .unwrap_or_default();
 
+    // Assigns the `counter = arg` counter to `Bencher` before benchmarking.
+    let arg_counter_tokens = options
+        .arg_counter
+        .as_ref()
+        .map(|counter| quote! { .counter(#counter) })
+        .unwrap_or_default();
+
     // Creates a function expr for the benchmarking function, optionally
     // monomorphized with generic parameters.
     let make_bench_fn = |generics: &[&dyn ToTokens]| {
@@ -249,7 +256,7 @@ pub fn bench(options: TokenStream, item: TokenStream) -> TokenStream {
 
                     |arg| #private_mod::ToStringHelper(arg).to_string(),
 
-                    |divan, __divan_arg| divan.bench(|| #fn_expr(
+                    |divan, __divan_arg| divan #arg_counter_tokens .bench(|| #fn_expr(
                         #private_mod::Arg::<#last_arg_type_tokens>::get(__divan_arg)
                     )),
                 ))
@@ -263,7 +270,7 @@ pub fn bench(options: TokenStream, item: TokenStream) -> TokenStream {
                     |arg| #private_mod::ToStringHelper(arg).to_string(),
 
                     |divan, __divan_arg| #fn_expr(
-                        divan,
+                        divan #arg_counter_tokens,
                         #private_mod::Arg::<#last_arg_type_tokens>::get(__divan_arg),
                     ),
                 ))
@@ -592,6 +599,9 @@ fn entry_meta_expr(
 
     let bench_options_fn = options.bench_options_fn(ignore_attr_ident);
 
+    let custom_meta_keys = options.custom_meta.iter().map(|(key, _)| key);
+    let custom_meta_values = options.custom_meta.iter().map(|(_, value)| value);
+
     quote! {
         #private_mod::EntryMeta {
             raw_name: #raw_name,
@@ -605,6 +615,8 @@ fn entry_meta_expr(
                 col: #std_crate::column!(),
             },
 
+            custom_meta: &[#((#custom_meta_keys, #custom_meta_values)),*],
+
             get_bench_options: #bench_options_fn,
             cached_bench_options: #private_mod::OnceLock::new(),
         }
@@ -0,0 +1,335 @@
+//! Merges results of multiple benchmark binaries into one report.
+
+use std::{
+    fmt, io,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    config::{TableStyle, ThousandsSeparator},
+    util,
+};
+
+/// Merges results of multiple benchmark binaries into one tree keyed by crate
+/// name, for workspaces with benchmarks spread across many crates.
+///
+/// Results are read from the terse output format (`--format terse`), which
+/// has one benchmark per line with its full path, median time, and any
+/// throughputs and metadata. Results can be added by running benchmark binaries
+/// with [`Aggregate::run`] or by loading saved outputs with
+/// [`Aggregate::add_terse`]. Adding multiple binaries under the same crate name
+/// merges their results.
+///
+/// The report is printed via [`Display`](fmt::Display) as a tree with crates at
+/// the top level, using the [`table_style`](Self::table_style) and
+/// [`thousands_separator`](Self::thousands_separator) set on it. Metadata is
+/// printed after all measurements, since benchmarks may have different counters.
+///
+/// # Examples
+///
+/// Benchmark binaries can be built without running them via
+/// `cargo bench --no-run`, which prints their paths:
+///
+/// ```no_run
+/// use std::process::Command;
+///
+/// let mut aggregate = divan::Aggregate::new();
+///
+/// aggregate.run("parser", Command::new("target/release/deps/parse-0123456789abcdef"))?;
+/// aggregate.run("server", Command::new("target/release/deps/routes-fedcba9876543210"))?;
+///
+/// println!("{aggregate}");
+/// # Ok::<_, std::io::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct Aggregate {
+    crates: Vec<Node>,
+    table_style: TableStyle,
+    thousands_separator: ThousandsSeparator,
+}
+
+#[derive(Debug)]
+struct Node {
+    name: String,
+
+    /// The median time followed by any throughputs and metadata, if this is a
+    /// benchmark.
+    fields: Vec<String>,
+
+    children: Vec<Node>,
+}
+
+impl Aggregate {
+    /// Creates an empty report.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the characters used to draw the tree of the report.
+    ///
+    /// This matches [`Divan::table_style`](crate::Divan::table_style) of the
+    /// benchmark binaries.
+    #[inline]
+    pub fn table_style(mut self, style: TableStyle) -> Self {
+        self.table_style = style;
+        self
+    }
+
+    /// Sets the separator between groups of thousands in measurements of the
+    /// report.
+    ///
+    /// This matches
+    /// [`Divan::thousands_separator`](crate::Divan::thousands_separator) of the
+    /// benchmark binaries.
+    #[inline]
+    pub fn thousands_separator(mut self, separator: ThousandsSeparator) -> Self {
+        self.thousands_separator = separator;
+        self
+    }
+
+    /// Runs a benchmark binary and adds its results under `crate_name`.
+    ///
+    /// The `--bench --format terse` arguments are appended to `command`, and
+    /// its standard output is captured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary cannot be run, if it fails, or if its
+    /// output is not UTF-8.
+    pub fn run(&mut self, crate_name: &str, mut command: Command) -> io::Result<()> {
+        let output = command
+            .args(["--bench", "--format", "terse"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::inherit())
+            .output()?;
+
+        if !output.status.success() {
+            let program = command.get_program().to_string_lossy().into_owned();
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("benchmarks of '{crate_name}' failed ('{program}' {})", output.status),
+            ));
+        }
+
+        let export = String::from_utf8(output.stdout)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        self.add_terse(crate_name, &export);
+        Ok(())
+    }
+
+    /// Adds results written with `--format terse` under `crate_name`.
+    ///
+    /// Lines without a median time are ignored. If a benchmark was already
+    /// added, its results are replaced.
+    pub fn add_terse(&mut self, crate_name: &str, export: &str) {
+        for line in export.lines() {
+            let mut fields = line.split('\t');
+
+            let Some(path) = fields.next().filter(|path| !path.is_empty()) else {
+                continue;
+            };
+
+            let fields: Vec<String> = fields.map(str::to_owned).collect();
+            if fields.is_empty() {
+                continue;
+            }
+
+            let mut node = Node::child(&mut self.crates, crate_name);
+            for component in path.split("::") {
+                node = Node::child(&mut node.children, component);
+            }
+
+            node.fields = fields;
+        }
+    }
+
+    /// Returns `true` if no results were added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.crates.is_empty()
+    }
+}
+
+impl Node {
+    /// Returns the child with `name`, inserting it if missing.
+    fn child<'a>(nodes: &'a mut Vec<Node>, name: &str) -> &'a mut Node {
+        let index = match nodes.iter().position(|node| node.name == name) {
+            Some(index) => index,
+            None => {
+                nodes.push(Node {
+                    name: name.to_owned(),
+                    fields: Vec::new(),
+                    children: Vec::new(),
+                });
+                nodes.len() - 1
+            }
+        };
+
+        &mut nodes[index]
+    }
+}
+
+/// The tree-drawn name of a node, followed by its formatted measurements and
+/// metadata.
+struct Row {
+    name: String,
+    measurements: Vec<String>,
+    meta: Vec<String>,
+}
+
+impl fmt::Display for Aggregate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = self.table_style;
+        let separator = self.thousands_separator.char();
+
+        /// Collects the row of each node.
+        fn collect_rows(
+            nodes: &[Node],
+            prefix: &str,
+            is_top_level: bool,
+            style: TableStyle,
+            separator: Option<char>,
+            rows: &mut Vec<Row>,
+        ) {
+            for (i, node) in nodes.iter().enumerate() {
+                let is_last = i == nodes.len() - 1;
+
+                let (name, child_prefix) = if is_top_level {
+                    (node.name.clone(), String::new())
+                } else {
+                    (
+                        format!("{prefix}{}{}", style.branch(is_last), node.name),
+                        format!("{prefix}{}", style.indent(is_last)),
+                    )
+                };
+
+                // Metadata pairs are written as `key=value` by the terse
+                // format, which measurements never contain.
+                let (meta, measurements): (Vec<&String>, Vec<&String>) =
+                    node.fields.iter().partition(|field| field.contains('='));
+
+                rows.push(Row {
+                    name,
+                    measurements: measurements
+                        .into_iter()
+                        .map(|field| {
+                            util::fmt::group_thousands(&style.units(field), separator).into_owned()
+                        })
+                        .collect(),
+                    meta: meta.into_iter().cloned().collect(),
+                });
+
+                collect_rows(&node.children, &child_prefix, false, style, separator, rows);
+            }
+        }
+
+        let mut rows = Vec::new();
+        collect_rows(&self.crates, "", true, style, separator, &mut rows);
+
+        let name_width = rows.iter().map(|row| row.name.chars().count()).max().unwrap_or(0);
+
+        let mut field_widths = Vec::<usize>::new();
+        for row in &rows {
+            if field_widths.len() < row.measurements.len() {
+                field_widths.resize(row.measurements.len(), 0);
+            }
+
+            for (width, field) in field_widths.iter_mut().zip(&row.measurements) {
+                *width = (*width).max(field.chars().count());
+            }
+        }
+
+        for row in rows {
+            let mut line = format!("{:name_width$}", row.name);
+
+            for (i, &width) in field_widths.iter().enumerate() {
+                if row.meta.is_empty() && i >= row.measurements.len() {
+                    break;
+                }
+
+                let field = row.measurements.get(i).map_or("", String::as_str);
+                line.push_str("  ");
+                line.push_str(&format!("{field:width$}"));
+            }
+
+            for meta in &row.meta {
+                line.push_str("  ");
+                line.push_str(meta);
+            }
+
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge() {
+        let mut aggregate = Aggregate::new();
+        assert!(aggregate.is_empty());
+
+        aggregate.add_terse("core", "math::add\t1.2 ns\nmath::mul\t1.3 ns\n");
+        aggregate.add_terse("core", "strings::len\t2 ns\t1 GB/s\n");
+        aggregate.add_terse("server", "routes::get\t10 µs\nroutes\n\n");
+
+        assert_eq!(
+            aggregate.to_string(),
+            "\
+core
+├─ math
+│  ├─ add   1.2 ns
+│  ╰─ mul   1.3 ns
+╰─ strings
+   ╰─ len   2 ns    1 GB/s
+server
+╰─ routes
+   ╰─ get   10 µs
+"
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let mut aggregate = Aggregate::new();
+
+        aggregate.add_terse("core", "add\t1 ns\n");
+        aggregate.add_terse("core", "add\t2 ns\n");
+
+        assert_eq!(aggregate.to_string(), "core\n╰─ add  2 ns\n");
+    }
+
+    #[test]
+    fn style() {
+        let mut aggregate = Aggregate::new()
+            .table_style(TableStyle::Ascii)
+            .thousands_separator(ThousandsSeparator::Comma);
+
+        aggregate.add_terse("core", "sleep\t1500 s\nspin\t10 µs\n");
+
+        assert_eq!(aggregate.to_string(), "core\n|- sleep  1,500 s\n`- spin   10 us\n");
+    }
+
+    /// Metadata is not aligned with measurements of other benchmarks.
+    #[test]
+    fn meta() {
+        let mut aggregate = Aggregate::new();
+
+        aggregate.add_terse("core", "add\t1 ns\tteam=math\nlen\t2 ns\t1 GB/s\tteam=strings\n");
+
+        assert_eq!(
+            aggregate.to_string(),
+            "\
+core
+├─ add  1 ns          team=math
+╰─ len  2 ns  1 GB/s  team=strings
+"
+        );
+    }
+}
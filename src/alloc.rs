@@ -200,6 +200,15 @@ impl<A> AllocProfiler<A> {
     pub const fn new(alloc: A) -> Self {
         Self { alloc }
     }
+
+    /// Returns the wrapped allocator, such as for reading statistics that it
+    /// tracks itself.
+    ///
+    /// Allocations made directly through it are not profiled.
+    #[inline]
+    pub const fn get_ref(&self) -> &A {
+        &self.alloc
+    }
 }
 
 /// Thread-local allocation information.
@@ -0,0 +1,410 @@
+//! Persists per-iteration sample times for comparing against later runs.
+//!
+//! Baselines are shared by builds of the same benchmark with different cargo
+//! features, so that features can be compared against each other.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    num::NonZeroU64,
+};
+
+use crate::{time::FineDuration, util};
+
+/// Prefix of the line recording the cargo features of the saved run.
+const FEATURES_PREFIX: &str = "#features\t";
+
+/// Prefix of the line recording the CPU timestamp counter frequency of the
+/// saved run.
+const TSC_FREQUENCY_PREFIX: &str = "#tsc-frequency\t";
+
+/// Prefix of the line recording the `meta(...)` pairs of the benchmark whose
+/// samples are on the next line.
+const META_PREFIX: &str = "#meta\t";
+
+/// Per-iteration sample times of benchmarks from a previous run.
+pub(crate) struct Baseline {
+    /// Cargo features enabled for the saved run, if they were recorded.
+    features: Option<Vec<String>>,
+
+    /// CPU timestamp counter frequency of the saved run, if it was the timer.
+    tsc_frequency: Option<NonZeroU64>,
+
+    samples: HashMap<String, Vec<FineDuration>>,
+
+    /// Custom `meta(...)` pairs of benchmarks that have them.
+    meta: HashMap<String, Vec<(String, String)>>,
+}
+
+impl Baseline {
+    /// Returns the per-iteration sample times of the benchmark at `entry_path`.
+    pub fn get(&self, entry_path: &str) -> Option<&[FineDuration]> {
+        self.samples.get(entry_path).map(Vec::as_slice)
+    }
+
+    /// Returns the cargo features enabled for the saved run, if they were
+    /// recorded.
+    pub fn features(&self) -> Option<&[String]> {
+        self.features.as_deref()
+    }
+
+    /// Returns the CPU timestamp counter frequency of the saved run, if it was
+    /// the timer.
+    pub fn tsc_frequency(&self) -> Option<NonZeroU64> {
+        self.tsc_frequency
+    }
+
+    /// Parses lines of `path<TAB>picos picos ...`, each optionally preceded by
+    /// `#meta<TAB>path<TAB>key=value<TAB>...`, along with
+    /// `#features<TAB>feature,feature,...` and `#tsc-frequency<TAB>hertz`.
+    ///
+    /// Later lines take precedence, so that benchmarks recorded again are
+    /// appended rather than rewritten.
+    fn parse(contents: &str) -> Self {
+        let features =
+            contents.lines().rev().find_map(|line| line.strip_prefix(FEATURES_PREFIX)).map(
+                |features| {
+                    features.split(',').filter(|f| !f.is_empty()).map(str::to_owned).collect()
+                },
+            );
+
+        let tsc_frequency = contents
+            .lines()
+            .rev()
+            .find_map(|line| line.strip_prefix(TSC_FREQUENCY_PREFIX)?.parse().ok());
+
+        let mut samples = HashMap::new();
+        let mut meta = HashMap::new();
+
+        // Metadata applies to the samples line right after it.
+        let mut prev_meta: Option<(&str, Vec<(String, String)>)> = None;
+
+        for line in contents.lines() {
+            if let Some(line) = line.strip_prefix(META_PREFIX) {
+                let mut fields = line.split('\t');
+                let path = fields.next().unwrap_or_default();
+                let pairs = fields
+                    .filter_map(|pair| pair.split_once('='))
+                    .map(|(key, value)| (unescape_meta(key), unescape_meta(value)))
+                    .collect();
+
+                prev_meta = Some((path, pairs));
+                continue;
+            }
+
+            let line_meta = prev_meta.take();
+
+            if line.starts_with('#') {
+                continue;
+            }
+
+            let Some((path, line_samples)) = line.split_once('\t') else {
+                continue;
+            };
+            let Some(line_samples) = line_samples
+                .split(' ')
+                .map(|picos| picos.parse().ok().map(|picos| FineDuration { picos }))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            samples.insert(path.to_owned(), line_samples);
+
+            match line_meta.filter(|&(meta_path, _)| meta_path == path) {
+                Some((_, pairs)) => meta.insert(path.to_owned(), pairs),
+                None => meta.remove(path),
+            };
+        }
+
+        Self { features, tsc_frequency, samples, meta }
+    }
+}
+
+/// Returns the path of the file storing the baseline called `name`.
+fn baseline_path(name: &str) -> Option<std::path::PathBuf> {
+    util::exe_data_path(&format!(".divan-baseline.{name}"), true)
+}
+
+/// Loads the baseline called `name`, warning if it cannot be read.
+pub(crate) fn load(name: &str) -> Option<Baseline> {
+    let result =
+        baseline_path(name).ok_or(io::ErrorKind::NotFound.into()).and_then(fs::read_to_string);
+
+    match result {
+        Ok(contents) => Some(Baseline::parse(&contents)),
+        Err(error) => {
+            eprintln!("warning: Failed to load baseline '{name}' ({error})");
+            None
+        }
+    }
+}
+
+/// Records per-iteration sample times and `meta(...)` pairs of the benchmark at
+/// `entry_path` into the baseline called `name`.
+pub(crate) fn record(
+    name: &str,
+    entry_path: &str,
+    samples: &[FineDuration],
+    custom_meta: &[(&str, &str)],
+) {
+    let result = baseline_path(name).ok_or(io::ErrorKind::NotFound.into()).and_then(|path| {
+        let line = meta_line(entry_path, custom_meta) + &samples_line(entry_path, samples);
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())
+    });
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to save '{entry_path}' to baseline '{name}' ({error})");
+    }
+}
+
+/// Records the cargo features enabled for this run into the baseline called
+/// `name`.
+pub(crate) fn record_features(name: &str, features: &[String]) {
+    let result = baseline_path(name).ok_or(io::ErrorKind::NotFound.into()).and_then(|path| {
+        let line = format!("{FEATURES_PREFIX}{}\n", features.join(","));
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())
+    });
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to save features to baseline '{name}' ({error})");
+    }
+}
+
+/// Records the CPU timestamp counter frequency of this run into the baseline
+/// called `name`.
+pub(crate) fn record_tsc_frequency(name: &str, frequency: NonZeroU64) {
+    let result = baseline_path(name).ok_or(io::ErrorKind::NotFound.into()).and_then(|path| {
+        let line = format!("{TSC_FREQUENCY_PREFIX}{frequency}\n");
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(line.as_bytes())
+    });
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to save TSC frequency to baseline '{name}' ({error})");
+    }
+}
+
+/// Formats a line of `path<TAB>picos picos ...`.
+fn samples_line(entry_path: &str, samples: &[FineDuration]) -> String {
+    let mut line = format!("{entry_path}\t");
+    for (i, sample) in samples.iter().enumerate() {
+        if i != 0 {
+            line.push(' ');
+        }
+        line.push_str(&sample.picos.to_string());
+    }
+    line.push('\n');
+    line
+}
+
+/// Formats a line of `#meta<TAB>path<TAB>key=value<TAB>...`, or nothing if
+/// there are no pairs.
+///
+/// Keys and values are escaped so that tabs and line breaks cannot end their
+/// field or line.
+fn meta_line<K: AsRef<str>, V: AsRef<str>>(entry_path: &str, custom_meta: &[(K, V)]) -> String {
+    if custom_meta.is_empty() {
+        return String::new();
+    }
+
+    let mut line = format!("{META_PREFIX}{entry_path}");
+    for (key, value) in custom_meta {
+        line.extend(["\t", &escape_meta(key.as_ref()), "=", &escape_meta(value.as_ref())]);
+    }
+    line.push('\n');
+    line
+}
+
+/// Escapes backslashes, tabs, and line breaks in a `meta(...)` key or value.
+fn escape_meta(s: &str) -> Cow<'_, str> {
+    if !s.contains(['\\', '\t', '\n', '\r']) {
+        return Cow::Borrowed(s);
+    }
+
+    let mut result = String::with_capacity(s.len() + 2);
+    for ch in s.chars() {
+        match ch {
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            _ => result.push(ch),
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// Reverses [`escape_meta`].
+fn unescape_meta(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            result.push(ch);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Prepares the baseline called `name` for recording a new run.
+///
+/// Samples of benchmarks that do not run again, such as because of filters or
+/// `--shard`, are kept. Benchmarks that run again are appended and take
+/// precedence over their previous samples.
+pub(crate) fn start_recording(name: &str) {
+    let Some(path) = baseline_path(name) else {
+        return;
+    };
+
+    let result = match fs::read_to_string(&path) {
+        Ok(contents) => fs::write(&path, compact(&contents)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to update baseline '{name}' ({error})");
+    }
+}
+
+/// Returns the samples of `contents` with one line per benchmark, without
+/// lines describing the previous run.
+fn compact(contents: &str) -> String {
+    let baseline = Baseline::parse(contents);
+
+    let mut entries: Vec<_> = baseline.samples.iter().collect();
+    entries.sort_unstable_by_key(|(path, _)| path.as_str());
+
+    entries
+        .into_iter()
+        .map(|(path, samples)| {
+            let custom_meta = baseline.meta.get(path).map(Vec::as_slice).unwrap_or_default();
+            meta_line(path, custom_meta) + &samples_line(path, samples)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let baseline = Baseline::parse("a::b\t1 2 3\nc\t4\ninvalid\nd\t5 x\n");
+
+        let picos = |path: &str| -> Option<Vec<u128>> {
+            Some(baseline.get(path)?.iter().map(|sample| sample.picos).collect())
+        };
+
+        assert_eq!(picos("a::b"), Some(vec![1, 2, 3]));
+        assert_eq!(picos("c"), Some(vec![4]));
+        assert_eq!(picos("invalid"), None);
+        assert_eq!(picos("d"), None);
+        assert_eq!(baseline.features(), None);
+    }
+
+    #[test]
+    fn parse_rerecorded() {
+        let baseline = Baseline::parse("#features\ta\na\t1\nb\t2\n#features\tb\na\t3 4\n");
+
+        assert_eq!(baseline.features(), Some(&["b".to_owned()][..]));
+        assert_eq!(baseline.get("a").map(<[_]>::len), Some(2));
+        assert_eq!(baseline.get("b").map(<[_]>::len), Some(1));
+    }
+
+    #[test]
+    fn compact() {
+        let contents = "#features\ta\n#tsc-frequency\t1\nb\t2\na\t1\nb\t3 4\n";
+        assert_eq!(super::compact(contents), "a\t1\nb\t3 4\n");
+        assert_eq!(super::compact(""), "");
+    }
+
+    #[test]
+    fn parse_meta() {
+        let contents = "#meta\ta\tteam=storage\tticket=PERF-42\na\t1\n#meta\tb\tteam=x\nc\t2\n";
+        let baseline = Baseline::parse(contents);
+
+        let pairs = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect()
+        };
+
+        assert_eq!(
+            baseline.meta.get("a"),
+            Some(&pairs(&[("team", "storage"), ("ticket", "PERF-42")]))
+        );
+        assert_eq!(baseline.get("a").map(<[_]>::len), Some(1));
+
+        // Metadata only applies to the samples of the same benchmark.
+        assert_eq!(baseline.meta.get("b"), None);
+        assert_eq!(baseline.meta.get("c"), None);
+        assert_eq!(baseline.get("c").map(<[_]>::len), Some(1));
+
+        // Recording again without metadata removes it.
+        let baseline = Baseline::parse(&format!("{contents}a\t3\n"));
+        assert_eq!(baseline.meta.get("a"), None);
+
+        // Compacting keeps metadata.
+        assert_eq!(
+            super::compact(contents),
+            "#meta\ta\tteam=storage\tticket=PERF-42\na\t1\nc\t2\n"
+        );
+    }
+
+    /// Values with tabs, line breaks, and escape characters do not break the
+    /// line format.
+    #[test]
+    fn meta_round_trip() {
+        let custom_meta = [("note", "a\tb\r\nc=d \\t \\"), ("team", "storage")];
+
+        let contents = format!(
+            "{}{}",
+            super::meta_line("a", &custom_meta),
+            super::samples_line("a", &[FineDuration { picos: 1 }])
+        );
+        assert_eq!(contents.lines().count(), 2);
+
+        let baseline = Baseline::parse(&contents);
+        let expected: Vec<(String, String)> =
+            custom_meta.iter().map(|&(k, v)| (k.to_owned(), v.to_owned())).collect();
+        assert_eq!(baseline.meta.get("a"), Some(&expected));
+        assert_eq!(baseline.get("a").map(<[_]>::len), Some(1));
+    }
+
+    #[test]
+    fn parse_features() {
+        let baseline = Baseline::parse("#features\tsimd,std\na\t1\n");
+        assert_eq!(baseline.features(), Some(&["simd".to_owned(), "std".to_owned()][..]));
+        assert_eq!(baseline.get("a").map(<[_]>::len), Some(1));
+
+        let baseline = Baseline::parse("#features\t\na\t1\n");
+        assert_eq!(baseline.features(), Some(&[][..]));
+    }
+
+    #[test]
+    fn parse_tsc_frequency() {
+        let baseline = Baseline::parse("#tsc-frequency\t3000000000\na\t1\n");
+        assert_eq!(baseline.tsc_frequency(), NonZeroU64::new(3_000_000_000));
+        assert_eq!(baseline.get("#tsc-frequency"), None);
+        assert_eq!(baseline.get("a").map(<[_]>::len), Some(1));
+
+        let baseline = Baseline::parse("a\t1\n");
+        assert_eq!(baseline.tsc_frequency(), None);
+    }
+}
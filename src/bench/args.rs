@@ -47,6 +47,13 @@ struct ErasedArgsSlice {
 unsafe impl Send for ErasedArgsSlice {}
 unsafe impl Sync for ErasedArgsSlice {}
 
+impl Default for BenchArgs {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BenchArgs {
     /// Creates an uninitialized instance.
     pub const fn new() -> Self {
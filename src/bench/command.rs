@@ -0,0 +1,41 @@
+use std::process::{Command, Stdio};
+
+/// Creates a copy of `command` for a single benchmark iteration, with standard
+/// I/O discarded.
+///
+/// `Command` does not implement `Clone`, so this copies what it exposes: the
+/// program, arguments, environment changes, and working directory.
+pub(crate) fn copy_command(command: &Command) -> Command {
+    let mut copy = Command::new(command.get_program());
+    copy.args(command.get_args());
+
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+
+    if let Some(dir) = command.get_current_dir() {
+        copy.current_dir(dir);
+    }
+
+    copy.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    copy
+}
+
+/// Runs `command` to completion.
+///
+/// # Panics
+///
+/// Panics if the process cannot be spawned or exits unsuccessfully.
+#[track_caller]
+pub(crate) fn run_command(mut command: Command) {
+    let program = command.get_program().to_string_lossy().into_owned();
+
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => panic!("Command '{program}' failed ({status})"),
+        Err(error) => panic!("Failed to run command '{program}' ({error})"),
+    }
+}
@@ -3,8 +3,10 @@ use std::{
     fmt,
     mem::{self, MaybeUninit},
     num::NonZeroUsize,
+    process::Command,
     sync::Barrier,
     thread,
+    time::Duration,
 };
 
 use crate::{
@@ -13,13 +15,17 @@ use crate::{
         ThreadAllocTallyMap, TotalAllocTallyMap,
     },
     black_box, black_box_drop,
+    budget::{self, RunBudget},
     counter::{
         AnyCounter, AsCountUInt, BytesCount, CharsCount, Counter, CounterCollection, IntoCounter,
-        ItemsCount, KnownCounterKind, MaxCountUInt,
+        ItemsCount, KnownCounterKind, LinesCount, MaxCountUInt,
     },
     divan::SharedContext,
-    stats::{RawSample, SampleCollection, Stats, StatsSet, ThreadSample, TimeSample},
-    time::{FineDuration, Timestamp, UntaggedTimestamp},
+    samples::Samples,
+    stats::{
+        RawSample, RunningVariance, SampleCollection, Stats, StatsSet, ThreadSample, TimeSample,
+    },
+    time::{FineDuration, Timer, Timestamp, UntaggedTimestamp},
     util::{self, SyncWrap, Unit},
 };
 
@@ -27,6 +33,7 @@ use crate::{
 mod tests;
 
 mod args;
+mod command;
 mod defer;
 mod options;
 
@@ -131,6 +138,37 @@ impl<'a, 'b> Bencher<'a, 'b> {
         self.with_inputs(|| ()).bench_local_values(|_: ()| benched());
     }
 
+    /// Benchmarks running `command` as a subprocess until it exits.
+    ///
+    /// A copy of `command` is prepared before each iteration, so that only
+    /// spawning and waiting on the process is timed. This allows benchmarking
+    /// CLI binaries in the same suite as library code.
+    ///
+    /// The program, arguments, environment changes, and working directory of
+    /// `command` are used. Standard I/O of the process is discarded, and
+    /// [`Command::env_clear`] has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::process::Command;
+    ///
+    /// #[divan::bench]
+    /// fn git_version(bencher: divan::Bencher) {
+    ///     let mut command = Command::new("git");
+    ///     command.arg("--version");
+    ///
+    ///     bencher.bench_command(command);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the process cannot be spawned or exits unsuccessfully.
+    pub fn bench_command(self, command: Command) {
+        self.with_inputs(|| command::copy_command(&command)).bench_values(command::run_command);
+    }
+
     /// Generate inputs for the [benchmarked function](#input-bench).
     ///
     /// Time spent generating inputs does not affect benchmark timing.
@@ -288,6 +326,7 @@ where
             KnownCounterKind::Items => self.input_counter(|c| ItemsCount::from(c)),
             KnownCounterKind::Bytes => self.input_counter(|c| BytesCount::from(c)),
             KnownCounterKind::Chars => self.input_counter(|c| CharsCount::from(c)),
+            KnownCounterKind::Lines => self.input_counter(|c| LinesCount::from(c)),
         }
     }
 
@@ -522,6 +561,10 @@ pub(crate) struct BenchContext<'a> {
     /// Whether the benchmark loop was started.
     pub did_run: bool,
 
+    /// Whether sampling stopped early because a single iteration exceeded
+    /// `max_sample_time`.
+    pub exceeded_max_sample_time: bool,
+
     /// The number of threads to run the benchmark. The default is 1.
     ///
     /// When set to 1, the benchmark loop is guaranteed to stay on the current
@@ -547,6 +590,7 @@ impl<'a> BenchContext<'a> {
             options,
             thread_count,
             did_run: false,
+            exceeded_max_sample_time: false,
             samples: SampleCollection::default(),
             counters: options.counters.to_collection(),
         }
@@ -632,6 +676,9 @@ impl<'a> BenchContext<'a> {
         // The remaining time left for benchmarking, in picoseconds.
         let max_picos = self.options.max_time().picos;
 
+        // The maximum time for a single sample, in picoseconds.
+        let max_sample_picos = self.options.max_sample_time().picos;
+
         // Don't bother running if user specifies 0 max time or 0 samples.
         if max_picos == 0 || !self.options.has_samples() {
             return;
@@ -654,8 +701,38 @@ impl<'a> BenchContext<'a> {
             self.samples.time_samples.reserve(self.options.sample_count.unwrap_or(1) as usize);
         }
 
+        // The CPU that the benchmarking thread was last observed on.
+        let mut last_cpu = if is_test { None } else { util::current_cpu() };
+        if last_cpu.is_some() {
+            self.samples.cpu_migrations = Some(0);
+        }
+
+        // Child processes are only accounted for once they have been waited on,
+        // so their CPU time is observed between samples.
+        if !is_test
+            && self.options.child_cpu_time.unwrap_or_default()
+            && util::children_cpu_time().is_some()
+        {
+            self.samples.child_cpu_time = Some(Duration::ZERO);
+        }
+
+        // Warm-up is done by the threads of the first sample.
+        let mut pending_warm_up = self.options.warm_up_time.filter(|_| !is_test);
+
+        // With a run budget, benchmarks without a fixed sample count stop once
+        // precise enough, or continue while noisy until their share is spent.
+        let bench_budget = self.shared_context.budget.as_ref().filter(|_| !is_test);
+        let budget_picos = bench_budget
+            .map(RunBudget::start_bench)
+            .filter(|_| self.options.sample_count.is_none())
+            .map(|share| FineDuration::from(share).picos);
+
+        // Variance of accepted samples collected with the current sample size.
+        let mut running_variance = RunningVariance::default();
+
         let skip_ext_time = self.options.skip_ext_time.unwrap_or_default();
-        let initial_start = if skip_ext_time { None } else { Some(Timestamp::start(timer_kind)) };
+        let mut initial_start =
+            if skip_ext_time { None } else { Some(Timestamp::start(timer_kind)) };
 
         while {
             // Conditions for when sampling is over:
@@ -663,6 +740,14 @@ impl<'a> BenchContext<'a> {
                 // Depleted the benchmarking time budget. This is a strict
                 // condition regardless of sample count and minimum time.
                 false
+            } else if let Some(budget_picos) = budget_picos.filter(|_| {
+                current_mode.is_collect() && running_variance.count() >= budget::MIN_SAMPLE_COUNT
+            }) {
+                let is_precise = running_variance
+                    .relative_standard_error()
+                    .map_or(true, |error| error <= budget::TARGET_RELATIVE_ERROR);
+
+                elapsed_picos < min_picos || (!is_precise && elapsed_picos < budget_picos)
             } else if rem_samples.unwrap_or(1) > 0 {
                 // More samples expected.
                 true
@@ -674,10 +759,20 @@ impl<'a> BenchContext<'a> {
             let sample_size = current_mode.sample_size();
             self.samples.sample_size = sample_size;
 
+            let child_cpu_start = self.samples.child_cpu_time.and(util::children_cpu_time());
+
             let barrier = if is_single_thread { None } else { Some(Barrier::new(thread_count)) };
 
+            // Every benchmarking thread warms up its own CPU before the threads
+            // sync to start timing, so that it is not included in the sample.
+            let warm_up_time = pending_warm_up.take();
+
             // Sample loop helper:
             let record_sample = |defer_store: &mut DeferStore<I, O>| -> RawSample {
+                if let Some(warm_up_time) = warm_up_time {
+                    util::spin(warm_up_time);
+                }
+
                 let mut counter_totals: [u128; KnownCounterKind::COUNT] =
                     [0; KnownCounterKind::COUNT];
 
@@ -754,6 +849,24 @@ impl<'a> BenchContext<'a> {
                 break;
             }
 
+            if let (Some(start), Some(end), Some(child_cpu_time)) =
+                (child_cpu_start, util::children_cpu_time(), &mut self.samples.child_cpu_time)
+            {
+                *child_cpu_time += end.saturating_sub(start);
+            }
+
+            // Observe migrations outside of timed regions. Multiple migrations
+            // within a sample are only observed once.
+            if let (Some(last_cpu), Some(cpu)) = (&mut last_cpu, util::current_cpu()) {
+                if *last_cpu != cpu {
+                    *last_cpu = cpu;
+
+                    if let Some(cpu_migrations) = &mut self.samples.cpu_migrations {
+                        *cpu_migrations += 1;
+                    }
+                }
+            }
+
             let slowest_sample = raw_samples.iter().max_by_key(|s| s.duration()).unwrap();
             let slowest_time = slowest_sample.duration();
 
@@ -768,6 +881,7 @@ impl<'a> BenchContext<'a> {
                 // Clear previous smaller samples.
                 self.samples.clear();
                 self.counters.clear_input_counts();
+                running_variance = RunningVariance::default();
 
                 // If within 100x timer precision, continue tuning.
                 let precision_multiple = slowest_time.picos / timer_precision.picos;
@@ -781,9 +895,11 @@ impl<'a> BenchContext<'a> {
 
             // Account the sample duration for the per-sample benchmarking
             // overhead.
+            let loop_overhead =
+                self.shared_context.bench_overhead.picos.saturating_mul(sample_size as u128);
+
             let sub_sample_overhead = {
-                let overhead =
-                    self.shared_context.bench_overhead.picos.saturating_mul(sample_size as u128);
+                let overhead = loop_overhead;
 
                 move |d: FineDuration| {
                     FineDuration {
@@ -807,10 +923,20 @@ impl<'a> BenchContext<'a> {
 
             for raw_sample in &raw_samples {
                 let sample_index = self.samples.time_samples.len();
+                let raw_duration = raw_sample.duration();
+
+                // Samples no longer than the sample loop itself are
+                // implausible, such as from the clock going backwards. Short
+                // samples can legitimately read as zero if the sample size was
+                // not tuned to exceed timer precision.
+                let is_rejected = !timer_precision.is_zero() && raw_duration.picos <= loop_overhead;
+
+                let duration = sub_sample_overhead(raw_duration);
+                if !is_rejected {
+                    running_variance.push(duration.picos as f64);
+                }
 
-                self.samples
-                    .time_samples
-                    .push(TimeSample { duration: sub_sample_overhead(raw_sample.duration()) });
+                self.samples.time_samples.push(TimeSample { duration, is_rejected });
 
                 if !raw_sample.alloc_tallies.is_empty() {
                     self.samples
@@ -833,11 +959,20 @@ impl<'a> BenchContext<'a> {
                     self.counters.push_counter(AnyCounter::known(counter_kind, per_iter_count));
                 }
 
+                // Rejected samples are replaced by later samples.
                 if let Some(rem_samples) = &mut rem_samples {
-                    *rem_samples = rem_samples.saturating_sub(1);
+                    if !is_rejected {
+                        *rem_samples = rem_samples.saturating_sub(1);
+                    }
                 }
             }
 
+            // Exclude warm-up from the elapsed time, such as for `max_time` and
+            // the time budget.
+            if let (Some(_), Some(initial_start)) = (warm_up_time, &mut initial_start) {
+                *initial_start = raw_samples.iter().map(|s| s.start).min().unwrap();
+            }
+
             if let Some(initial_start) = initial_start {
                 let last_end = raw_samples.iter().map(|s| s.end).max().unwrap();
                 elapsed_picos = last_end.duration_since(initial_start, timer).picos;
@@ -847,6 +982,81 @@ impl<'a> BenchContext<'a> {
                 let progress_picos = slowest_time.picos.max(1_000);
                 elapsed_picos = elapsed_picos.saturating_add(progress_picos);
             }
+
+            // Estimate how much of sampling is done, which is bounded by the
+            // sample count, maximum time, and minimum time.
+            let progress = &self.shared_context.progress;
+            if progress.is_enabled() && current_mode.is_collect() {
+                let collected = self.samples.time_samples.len() as f64;
+                let expected = collected + rem_samples.unwrap_or_default() as f64;
+
+                let mut done = (collected / expected).max(elapsed_picos as f64 / max_picos as f64);
+                if elapsed_picos < min_picos {
+                    done = done.min(elapsed_picos as f64 / min_picos as f64);
+                }
+
+                progress.update_bench(done);
+            }
+
+            // Prevent pathological inputs from taking too long by reducing the
+            // number of iterations in subsequent samples. Samples collected
+            // with the previous sample size are discarded because statistics
+            // assume a single sample size.
+            if slowest_time.picos > max_sample_picos {
+                if sample_size > 1 {
+                    let reduced_size = (sample_size as u128 * max_sample_picos
+                        / slowest_time.picos)
+                        .clamp(1, sample_size as u128 / 2);
+
+                    self.samples.clear();
+                    self.counters.clear_input_counts();
+                    running_variance = RunningVariance::default();
+
+                    current_mode = BenchMode::Collect { sample_size: reduced_size as u32 };
+                    rem_samples = Some(self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT));
+                } else {
+                    // A single iteration cannot be made shorter, so stop with
+                    // the samples collected so far.
+                    self.exceeded_max_sample_time = true;
+                    break;
+                }
+            }
+        }
+
+        // Multi-iteration samples average away the fastest iteration, so time
+        // single iterations separately within the remaining time budget. This
+        // is skipped if a single iteration already exceeded `max_sample_time`.
+        if !is_test
+            && is_single_thread
+            && !self.exceeded_max_sample_time
+            && self.options.fastest_iter.unwrap_or_default()
+        {
+            let sample_count = self.options.sample_count.unwrap_or(DEFAULT_SAMPLE_COUNT);
+            let overhead = self.shared_context.bench_overhead.picos;
+
+            let mut fastest_iter = FineDuration::default();
+
+            for _ in 0..sample_count {
+                let ([start, end], _) = record_sample(1, None, &mut defer_store, &mut |_| {});
+                let duration = end.duration_since(start, timer);
+
+                fastest_iter = fastest_iter
+                    .clamp_to_min(FineDuration { picos: duration.picos.saturating_sub(overhead) });
+
+                elapsed_picos = elapsed_picos.saturating_add(duration.picos.max(1_000));
+                if elapsed_picos >= max_picos {
+                    break;
+                }
+            }
+
+            // Iterations faster than the timer can resolve read as zero.
+            if !fastest_iter.is_zero() {
+                self.samples.fastest_iter = Some(fastest_iter);
+            }
+        }
+
+        if !is_test {
+            self.samples.reject_anomalies();
         }
     }
 
@@ -871,6 +1081,7 @@ impl<'a> BenchContext<'a> {
         //   time spent between samples.
 
         let timer_kind = self.shared_context.timer.kind();
+        let serialize = self.shared_context.serializing_fences;
 
         move |sample_size: usize,
               barrier: Option<&Barrier>,
@@ -951,7 +1162,7 @@ impl<'a> BenchContext<'a> {
                 }
 
                 sync_threads(true);
-                sample_start = UntaggedTimestamp::start(timer_kind);
+                sample_start = UntaggedTimestamp::start(timer_kind, serialize);
 
                 // Sample loop:
                 for _ in 0..sample_size {
@@ -962,7 +1173,7 @@ impl<'a> BenchContext<'a> {
                     mem::forget(black_box(benched(&input)));
                 }
 
-                sample_end = UntaggedTimestamp::end(timer_kind);
+                sample_end = UntaggedTimestamp::end(timer_kind, serialize);
                 sync_threads(false);
                 sum_alloc_tallies();
 
@@ -1004,7 +1215,7 @@ impl<'a> BenchContext<'a> {
                         let defer_slots_iter = defer_slots_slice.iter();
 
                         sync_threads(true);
-                        sample_start = UntaggedTimestamp::start(timer_kind);
+                        sample_start = UntaggedTimestamp::start(timer_kind, serialize);
 
                         // Sample loop:
                         for defer_slot in defer_slots_iter {
@@ -1017,7 +1228,7 @@ impl<'a> BenchContext<'a> {
                             }
                         }
 
-                        sample_end = UntaggedTimestamp::end(timer_kind);
+                        sample_end = UntaggedTimestamp::end(timer_kind, serialize);
                         sync_threads(false);
                         sum_alloc_tallies();
 
@@ -1057,7 +1268,7 @@ impl<'a> BenchContext<'a> {
                         let defer_inputs_iter = defer_inputs_slice.iter();
 
                         sync_threads(true);
-                        sample_start = UntaggedTimestamp::start(timer_kind);
+                        sample_start = UntaggedTimestamp::start(timer_kind, serialize);
 
                         // Sample loop:
                         for input in defer_inputs_iter {
@@ -1066,7 +1277,7 @@ impl<'a> BenchContext<'a> {
                             black_box_drop(unsafe { benched(input) });
                         }
 
-                        sample_end = UntaggedTimestamp::end(timer_kind);
+                        sample_end = UntaggedTimestamp::end(timer_kind, serialize);
                         sync_threads(false);
                         sum_alloc_tallies();
 
@@ -1101,16 +1312,57 @@ impl<'a> BenchContext<'a> {
             BenchMode::Test
         } else if let Some(sample_size) = self.options.sample_size {
             BenchMode::Collect { sample_size }
+        } else if let Timer::Mock = self.shared_context.timer {
+            // Virtual time may never exceed precision, so tuning may not end.
+            BenchMode::Collect { sample_size: 1 }
         } else {
             BenchMode::Tune { sample_size: 1 }
         }
     }
 
+    /// Returns the per-iteration duration of each sample.
+    pub fn iter_durations(&self) -> Vec<FineDuration> {
+        self.samples.iter_durations()
+    }
+
+    /// Returns accepted samples for analysis outside of Divan.
+    #[allow(clippy::unnecessary_cast)] // `MaxCountUInt` may be larger than `u64`.
+    pub fn public_samples(&self, path: &str) -> Samples {
+        let accepted_indices: Vec<usize> = (0..self.samples.time_samples.len())
+            .filter(|&i| !self.samples.time_samples[i].is_rejected)
+            .collect();
+
+        let counts = KnownCounterKind::ALL.map(|counter_kind| {
+            let counts = self.counters.counts(counter_kind);
+
+            if self.counters.uses_input_counts(counter_kind) {
+                accepted_indices
+                    .iter()
+                    .map(|&i| counts.get(i).map(|&count| count as u64))
+                    .collect::<Option<Vec<u64>>>()
+            } else {
+                let count = *counts.first()? as u64;
+                Some(vec![count; accepted_indices.len()])
+            }
+        });
+
+        Samples {
+            path: path.to_owned(),
+            threads: self.thread_count.get(),
+            sample_size: self.samples.sample_size,
+            durations: self
+                .samples
+                .accepted_samples()
+                .map(|sample| Duration::try_from(sample.duration).unwrap_or(Duration::MAX))
+                .collect(),
+            counts,
+        }
+    }
+
     pub fn compute_stats(&self) -> Stats {
-        let time_samples = &self.samples.time_samples;
         let alloc_samples = &self.samples.alloc_tallies;
 
-        let sample_count = time_samples.len();
+        let sample_count = self.samples.accepted_count();
         let sample_size = self.samples.sample_size;
 
         let total_count = self.samples.iter_count();
@@ -1128,6 +1380,10 @@ impl<'a> BenchContext<'a> {
             util::slice_ptr_index(&self.samples.time_samples, sample)
         };
 
+        let is_accepted_index = |index: usize| -> bool {
+            self.samples.time_samples.get(index).is_some_and(|sample| !sample.is_rejected)
+        };
+
         let counter_count_for_sample =
             |sample: &TimeSample, counter_kind: KnownCounterKind| -> Option<MaxCountUInt> {
                 let counts = self.counters.counts(counter_kind);
@@ -1175,7 +1431,7 @@ impl<'a> BenchContext<'a> {
                     .last()
                     .and_then(|s| counter_count_for_sample(s, counter_kind))?,
                 median,
-                mean: self.counters.mean_count(counter_kind),
+                mean: self.counters.mean_count(counter_kind, is_accepted_index),
             })
         });
 
@@ -1188,9 +1444,13 @@ impl<'a> BenchContext<'a> {
                 .unwrap_or_default()
         };
 
+        // Rejected samples are excluded from means, like they are from
+        // `total_count`.
         let mut alloc_total_tallies = TotalAllocTallyMap::default();
-        for alloc_info in alloc_samples.values() {
-            alloc_info.add_to_total(&mut alloc_total_tallies);
+        for (&index, alloc_info) in alloc_samples {
+            if is_accepted_index(index as usize) {
+                alloc_info.add_to_total(&mut alloc_total_tallies);
+            }
         }
 
         Stats {
@@ -1202,6 +1462,7 @@ impl<'a> BenchContext<'a> {
                 median: median_duration,
                 mean: mean_duration,
             },
+            total_time: total_duration,
             alloc_tallies: AllocOpMap {
                 values: AllocOp::ALL
                     .map(|op| StatsSet {
@@ -1250,6 +1511,20 @@ impl<'a> BenchContext<'a> {
                     .map(StatsSet::transpose),
             },
             counts,
+            run_variance: None,
+            cpu_migrations: self.samples.cpu_migrations,
+            rejected_count: self.samples.rejected_count() as u32,
+            fastest_iter: self.samples.fastest_iter,
+            child_cpu_time: self.samples.child_cpu_time.map(|child_cpu_time| {
+                // Rejected samples still ran their iterations.
+                let iter_count = self.samples.time_samples.len() as u128 * sample_size as u128;
+
+                FineDuration {
+                    picos: (child_cpu_time.as_nanos() * 1_000)
+                        .checked_div(iter_count)
+                        .unwrap_or_default(),
+                }
+            }),
         }
     }
 }
@@ -33,11 +33,32 @@ pub struct BenchOptions<'a> {
     /// The time ceiling for benchmarking a function.
     pub max_time: Option<Duration>,
 
+    /// The time ceiling for a single sample, after which the sample size is
+    /// reduced or sampling stops.
+    pub max_sample_time: Option<Duration>,
+
+    /// The time spent spinning the CPU before sampling, so that samples are
+    /// not taken while it is in a low-power state.
+    pub warm_up_time: Option<Duration>,
+
     /// When accounting for `min_time` or `max_time`, skip time external to
     /// benchmarked functions, such as time spent generating inputs and running
     /// [`Drop`].
     pub skip_ext_time: Option<bool>,
 
+    /// Whether to also time single iterations to report the fastest one,
+    /// which approximates the cost of a function without interference.
+    pub fastest_iter: Option<bool>,
+
+    /// Whether to also report the CPU time of child processes, for benchmarks
+    /// that spawn and wait on them.
+    pub child_cpu_time: Option<bool>,
+
+    /// The order in which the benchmark runs relative to others, where higher
+    /// priorities run earlier. This is 0 by default, which runs in display
+    /// order after prioritized benchmarks.
+    pub priority: Option<u32>,
+
     /// Whether the benchmark should be ignored.
     ///
     /// This may be set within the attribute or with a separate
@@ -59,7 +80,12 @@ impl<'a> BenchOptions<'a> {
             threads: self.threads.as_deref().or(other.threads.as_deref()).map(Cow::Borrowed),
             min_time: self.min_time.or(other.min_time),
             max_time: self.max_time.or(other.max_time),
+            max_sample_time: self.max_sample_time.or(other.max_sample_time),
+            warm_up_time: self.warm_up_time.or(other.warm_up_time),
             skip_ext_time: self.skip_ext_time.or(other.skip_ext_time),
+            fastest_iter: self.fastest_iter.or(other.fastest_iter),
+            child_cpu_time: self.child_cpu_time.or(other.child_cpu_time),
+            priority: self.priority.or(other.priority),
             ignore: self.ignore.or(other.ignore),
 
             // `Clone` values:
@@ -67,6 +93,26 @@ impl<'a> BenchOptions<'a> {
         }
     }
 
+    /// Returns options that do not borrow, so that they can outlive the
+    /// options they were merged from.
+    pub(crate) fn into_owned(self) -> BenchOptions<'static> {
+        BenchOptions {
+            sample_count: self.sample_count,
+            sample_size: self.sample_size,
+            threads: self.threads.map(|threads| Cow::Owned(threads.into_owned())),
+            counters: self.counters,
+            min_time: self.min_time,
+            max_time: self.max_time,
+            max_sample_time: self.max_sample_time,
+            warm_up_time: self.warm_up_time,
+            skip_ext_time: self.skip_ext_time,
+            fastest_iter: self.fastest_iter,
+            child_cpu_time: self.child_cpu_time,
+            priority: self.priority,
+            ignore: self.ignore,
+        }
+    }
+
     /// Returns `true` if non-zero samples are specified.
     #[inline]
     pub(crate) fn has_samples(&self) -> bool {
@@ -82,4 +128,9 @@ impl<'a> BenchOptions<'a> {
     pub(crate) fn max_time(&self) -> FineDuration {
         self.max_time.map(FineDuration::from).unwrap_or(FineDuration::MAX)
     }
+
+    #[inline]
+    pub(crate) fn max_sample_time(&self) -> FineDuration {
+        self.max_sample_time.map(FineDuration::from).unwrap_or(FineDuration::MAX)
+    }
 }
@@ -4,11 +4,13 @@
 use std::{
     collections::HashSet,
     sync::atomic::{AtomicUsize, Ordering::SeqCst},
+    time::Duration,
 };
 
 use super::*;
 use crate::{
     config::Action,
+    progress::Progress,
     time::{Timer, TimerKind},
 };
 
@@ -31,6 +33,20 @@ const THREAD_COUNTS: &[usize] = if cfg!(miri) {
     &[1, 2, 3, 4, 5, 6, 9]
 };
 
+/// Returns a context for benchmarking with `timer` and no other options.
+fn shared_context(timer: Timer) -> SharedContext {
+    SharedContext {
+        action: Action::Bench,
+        timer,
+        serializing_fences: false,
+        bench_overhead: FineDuration::default(),
+        baseline: None,
+        progress: Progress::default(),
+        budget: None,
+        early_results: Default::default(),
+    }
+}
+
 #[track_caller]
 fn test_bencher(test: &mut dyn FnMut(Bencher)) {
     let bench_options = BenchOptions {
@@ -41,8 +57,7 @@ fn test_bencher(test: &mut dyn FnMut(Bencher)) {
 
     for timer in Timer::available() {
         for action in [Action::Bench, Action::Test] {
-            let shared_context =
-                SharedContext { action, timer, bench_overhead: FineDuration::default() };
+            let shared_context = SharedContext { action, ..shared_context(timer) };
 
             for &thread_count in THREAD_COUNTS {
                 let mut bench_context = BenchContext::new(
@@ -124,6 +139,7 @@ mod run_count {
             match context.shared_context.timer.kind() {
                 TimerKind::Os => timer_os = true,
                 TimerKind::Tsc => timer_tsc = true,
+                TimerKind::Mock => unreachable!(),
             }
 
             let is_test = context.shared_context.action.is_test();
@@ -550,3 +566,313 @@ mod zst_input {
         assert_eq!(ZST_COUNT.load(SeqCst), 0);
     }
 }
+
+/// Tests that `max_sample_time` reduces the sample size of slow benchmarks and
+/// stops sampling when a single iteration is too slow.
+#[test]
+fn max_sample_time() {
+    use crate::time::MockClock;
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    #[track_caller]
+    fn test(sample_size: u32, max_sample_time: Duration, check: fn(&BenchContext)) {
+        let bench_options = BenchOptions {
+            sample_count: Some(SAMPLE_COUNT),
+            sample_size: Some(sample_size),
+            max_sample_time: Some(max_sample_time),
+            ..BenchOptions::default()
+        };
+
+        let shared_context = shared_context(Timer::Mock);
+
+        let mut bench_context =
+            BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+        Bencher::new(&mut bench_context).bench(|| MockClock::advance(Duration::from_millis(1)));
+
+        check(&bench_context);
+    }
+
+    // 64 iterations of 1ms get reduced to fit within 10ms.
+    test(64, Duration::from_millis(10), |bench_context| {
+        assert!(!bench_context.exceeded_max_sample_time);
+        assert_eq!(bench_context.samples.sample_size, 10);
+        assert_eq!(bench_context.samples.time_samples.len(), SAMPLE_COUNT as usize);
+    });
+
+    // A single 1ms iteration cannot be reduced.
+    test(1, Duration::from_micros(500), |bench_context| {
+        assert!(bench_context.exceeded_max_sample_time);
+        assert_eq!(bench_context.samples.sample_size, 1);
+        assert_eq!(bench_context.samples.time_samples.len(), 1);
+    });
+
+    // Single iterations are not timed again for `fastest_iter`.
+    let bench_options = BenchOptions {
+        sample_count: Some(SAMPLE_COUNT),
+        sample_size: Some(1),
+        max_sample_time: Some(Duration::from_micros(500)),
+        fastest_iter: Some(true),
+        ..BenchOptions::default()
+    };
+
+    let shared_context = shared_context(Timer::Mock);
+    let mut bench_context = BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+    let iter_count = AtomicUsize::new(0);
+    Bencher::new(&mut bench_context).bench(|| {
+        iter_count.fetch_add(1, SeqCst);
+        MockClock::advance(Duration::from_millis(1));
+    });
+
+    assert!(bench_context.exceeded_max_sample_time);
+    assert_eq!(iter_count.load(SeqCst), 1);
+    assert_eq!(bench_context.samples.fastest_iter, None);
+}
+
+/// Tests that public samples have a duration and counts for each sample.
+#[test]
+fn public_samples() {
+    use crate::time::MockClock;
+
+    // Samples timed by the OS clock may be rejected as anomalies.
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let bench_options = BenchOptions {
+        sample_count: Some(SAMPLE_COUNT),
+        sample_size: Some(2),
+        ..BenchOptions::default()
+    };
+
+    let shared_context = shared_context(Timer::Mock);
+
+    let mut bench_context = BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+    Bencher::new(&mut bench_context)
+        .with_inputs(|| vec![0u8; 3])
+        .input_counter(|input: &Vec<u8>| BytesCount::of_slice(input))
+        .bench_refs(|input| {
+            MockClock::advance(Duration::from_nanos(10));
+            input.len()
+        });
+
+    let samples = bench_context.public_samples("path::to::bench");
+    assert_eq!(samples.path(), "path::to::bench");
+    assert_eq!(samples.threads(), 1);
+    assert_eq!(samples.sample_size(), 2);
+    assert_eq!(samples.durations(), [Duration::from_nanos(20); SAMPLE_COUNT as usize]);
+    assert_eq!(samples.counts::<BytesCount>(), Some(&[3; SAMPLE_COUNT as usize][..]));
+    assert_eq!(samples.counts::<ItemsCount>(), None);
+}
+
+/// Tests that samples rejected as clock anomalies are excluded from mean
+/// allocation and counter statistics.
+#[test]
+fn rejected_sample_means() {
+    use crate::{alloc::AllocOp, time::MockClock};
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let bench_options =
+        BenchOptions { sample_count: Some(4), sample_size: Some(1), ..BenchOptions::default() };
+
+    let shared_context = shared_context(Timer::Mock);
+
+    let mut bench_context = BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+    // The third sample is much slower and larger than the others.
+    let input_index = AtomicUsize::new(0);
+    Bencher::new(&mut bench_context)
+        .with_inputs(|| if input_index.fetch_add(1, SeqCst) == 2 { 1_000 } else { 3 })
+        .input_counter(|&len: &usize| BytesCount::new(len))
+        .bench_values(|len| {
+            let time = if len == 3 { Duration::from_nanos(10) } else { Duration::from_millis(10) };
+            MockClock::advance(time);
+
+            Vec::<u8>::with_capacity(len)
+        });
+
+    let stats = bench_context.compute_stats();
+    assert_eq!(stats.rejected_count, 1);
+    assert_eq!(stats.counts[KnownCounterKind::Bytes as usize].as_ref().map(|c| c.mean), Some(3));
+
+    let alloc = stats.alloc_tallies.get(AllocOp::Alloc);
+    assert_eq!(alloc.count.mean, 1.0);
+    assert_eq!(alloc.size.mean, 3.0);
+}
+
+/// Tests that `bench_command` runs the command to completion each iteration.
+///
+/// Miri does not support spawning processes.
+#[cfg(all(unix, not(miri)))]
+mod bench_command {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn success() {
+        test_bencher(&mut |b| b.bench_command(Command::new("true")));
+    }
+
+    #[test]
+    #[should_panic = "Command 'false' failed"]
+    fn failure() {
+        test_bencher(&mut |b| b.bench_command(Command::new("false")));
+    }
+}
+
+/// Serializes tests that advance the mock clock, since it is shared by all
+/// threads.
+static MOCK_CLOCK_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Tests that the mock clock makes statistics and time budgets deterministic.
+#[test]
+fn mock_clock() {
+    use crate::time::MockClock;
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let shared_context = shared_context(Timer::Mock);
+
+    let bench = |bench_options: &BenchOptions| -> Stats {
+        let mut bench_context =
+            BenchContext::new(&shared_context, bench_options, NonZeroUsize::MIN);
+
+        Bencher::new(&mut bench_context).bench(|| MockClock::advance(Duration::from_nanos(10)));
+
+        bench_context.compute_stats()
+    };
+
+    // Sample sizes are not tuned and the clock only moves when advanced.
+    let stats =
+        bench(&BenchOptions { sample_count: Some(SAMPLE_COUNT), ..BenchOptions::default() });
+    assert_eq!(stats.sample_count, SAMPLE_COUNT);
+    assert_eq!(stats.iter_count, u64::from(SAMPLE_COUNT));
+    assert_eq!(stats.time.fastest, FineDuration { picos: 10_000 });
+    assert_eq!(stats.time.slowest, FineDuration { picos: 10_000 });
+    assert_eq!(stats.total_time, FineDuration { picos: 10_000 * SAMPLE_COUNT as u128 });
+
+    // The time budget is spent in virtual time: 5 samples of 2 × 10ns.
+    let stats = bench(&BenchOptions {
+        sample_count: Some(100),
+        sample_size: Some(2),
+        max_time: Some(Duration::from_nanos(100)),
+        skip_ext_time: Some(true),
+        ..BenchOptions::default()
+    });
+    assert_eq!(stats.sample_count, 5);
+    assert_eq!(stats.time.median, FineDuration { picos: 10_000 });
+}
+
+/// Tests that generating inputs with `Bencher::with_inputs` is not timed.
+#[test]
+fn untimed_inputs() {
+    use crate::time::MockClock;
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let shared_context = shared_context(Timer::Mock);
+
+    let bench_options = BenchOptions {
+        sample_count: Some(SAMPLE_COUNT),
+        sample_size: Some(4),
+        ..BenchOptions::default()
+    };
+
+    let gen_input = || {
+        MockClock::advance(Duration::from_micros(1));
+        make_string()
+    };
+
+    let mut bench_context = BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+    Bencher::new(&mut bench_context).with_inputs(gen_input).bench_values(|s| {
+        MockClock::advance(Duration::from_nanos(10));
+        s
+    });
+    assert_eq!(bench_context.compute_stats().time.slowest, FineDuration { picos: 10_000 });
+
+    let mut bench_context = BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+    Bencher::new(&mut bench_context).with_inputs(gen_input).bench_refs(|s| {
+        MockClock::advance(Duration::from_nanos(10));
+        s.push('!');
+    });
+    assert_eq!(bench_context.compute_stats().time.slowest, FineDuration { picos: 10_000 });
+}
+
+/// Tests that a run budget gives fewer samples to stable benchmarks and more to
+/// noisy ones.
+#[test]
+fn time_budget() {
+    use crate::{budget::RunBudget, time::MockClock};
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let shared_context = SharedContext {
+        budget: Some(RunBudget::new(Duration::from_secs(3600), 2, 1)),
+        ..shared_context(Timer::Mock)
+    };
+
+    let bench_options = BenchOptions { sample_size: Some(1), ..BenchOptions::default() };
+
+    let bench = |benched: &(dyn Fn() + Sync)| -> u32 {
+        let mut bench_context =
+            BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+        Bencher::new(&mut bench_context).bench(benched);
+
+        bench_context.compute_stats().sample_count
+    };
+
+    // Identical samples are precise as soon as variance can be estimated.
+    let stable_count = bench(&|| MockClock::advance(Duration::from_nanos(10)));
+    assert_eq!(stable_count, crate::budget::MIN_SAMPLE_COUNT as u32);
+
+    // Alternating 10ns and 30ns iterations need many samples for the standard
+    // error to be within 1% of the mean.
+    let is_slow = AtomicUsize::new(0);
+    let noisy_count = bench(&|| {
+        let nanos = if is_slow.fetch_xor(1, SeqCst) == 1 { 30 } else { 10 };
+        MockClock::advance(Duration::from_nanos(nanos));
+    });
+    assert!(noisy_count > DEFAULT_SAMPLE_COUNT, "{noisy_count}");
+}
+
+/// Tests that the sample loop overhead is subtracted from samples, so that an
+/// empty benchmark reads 0.
+#[test]
+fn loop_overhead() {
+    use crate::time::MockClock;
+
+    let _lock = MOCK_CLOCK_LOCK.lock().unwrap_or_else(|error| error.into_inner());
+
+    let bench_options = BenchOptions {
+        sample_count: Some(SAMPLE_COUNT),
+        sample_size: Some(4),
+        ..BenchOptions::default()
+    };
+
+    let median = |overhead_nanos: u128, iter_nanos: u64| -> FineDuration {
+        let shared_context = SharedContext {
+            bench_overhead: FineDuration { picos: overhead_nanos * 1_000 },
+            ..shared_context(Timer::Mock)
+        };
+
+        let mut bench_context =
+            BenchContext::new(&shared_context, &bench_options, NonZeroUsize::MIN);
+
+        Bencher::new(&mut bench_context)
+            .bench(|| MockClock::advance(Duration::from_nanos(iter_nanos)));
+
+        bench_context.compute_stats().time.median
+    };
+
+    // The clock does not move for an empty benchmark.
+    assert_eq!(median(0, 0), FineDuration::default());
+
+    // Iterations that only take as long as the loop itself read as 0.
+    assert_eq!(median(5, 5), FineDuration::default());
+
+    // Only the loop overhead is subtracted from slower iterations.
+    assert_eq!(median(5, 15), FineDuration { picos: 10_000 });
+}
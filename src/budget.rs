@@ -0,0 +1,88 @@
+//! Sharing a whole-run time budget between benchmarks.
+//!
+//! Each benchmark gets a share of the time remaining, so that time saved by
+//! stable benchmarks stopping early goes to noisier benchmarks that run later.
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+/// Stable benchmarks stop once the standard error of their mean time is this
+/// fraction of the mean.
+pub(crate) const TARGET_RELATIVE_ERROR: f64 = 0.01;
+
+/// Benchmarks always collect this many samples before stopping, so that their
+/// variance can be estimated.
+pub(crate) const MIN_SAMPLE_COUNT: u64 = 10;
+
+/// Noisy benchmarks may take up to this many times their fair share of the
+/// remaining time.
+const MAX_SHARE_MULTIPLE: u32 = 2;
+
+/// A time budget for all benchmarks of a run.
+pub(crate) struct RunBudget {
+    deadline: Instant,
+
+    /// The expected number of benchmarks in the run.
+    bench_count: usize,
+
+    /// The number of benchmarks measured at the same time, as with `--jobs`.
+    worker_count: u32,
+
+    /// The number of benchmarks that have started.
+    started_count: AtomicUsize,
+}
+
+impl RunBudget {
+    /// Returns a budget of `total` time for a run of `bench_count` benchmarks,
+    /// of which `worker_count` are measured at the same time.
+    pub fn new(total: Duration, bench_count: usize, worker_count: usize) -> Self {
+        Self {
+            deadline: Instant::now() + total,
+            bench_count,
+            worker_count: u32::try_from(worker_count).unwrap_or(u32::MAX).max(1),
+            started_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Marks the start of a benchmark and returns the most time it may take.
+    ///
+    /// This is a multiple of the fair share of the remaining time, so that
+    /// noisy benchmarks can collect more samples. Benchmarks measured at the
+    /// same time spend the remaining time together, so each gets a larger
+    /// share.
+    pub fn start_bench(&self) -> Duration {
+        let started_count = self.started_count.fetch_add(1, Relaxed);
+        let remaining_count = self.bench_count.saturating_sub(started_count).max(1);
+
+        let remaining_time = self.deadline.saturating_duration_since(Instant::now());
+        let fair_share = remaining_time.saturating_mul(self.worker_count)
+            / u32::try_from(remaining_count).unwrap_or(u32::MAX);
+
+        fair_share.saturating_mul(MAX_SHARE_MULTIPLE).min(remaining_time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_bench() {
+        let total = Duration::from_secs(1000);
+
+        // Twice the fair share of 1000s over 8 benchmarks.
+        let share = RunBudget::new(total, 8, 1).start_bench();
+        assert!(share <= Duration::from_secs(250) && share > Duration::from_secs(249));
+
+        // Two benchmarks at a time spend the remaining time twice as quickly.
+        let share = RunBudget::new(total, 8, 2).start_bench();
+        assert!(share <= Duration::from_secs(500) && share > Duration::from_secs(499));
+
+        // Shares never exceed the remaining time.
+        let budget = RunBudget::new(total, 2, 4);
+        assert!(budget.start_bench() <= total);
+        assert!(budget.start_bench() <= total);
+    }
+}
@@ -1,7 +1,10 @@
 use clap::{builder::PossibleValue, value_parser, Arg, ArgAction, ColorChoice, Command, ValueEnum};
 
 use crate::{
-    config::{ParsedSeconds, SortingAttr},
+    config::{
+        GridFormat, GridMetric, OutputFormat, ParsedSeconds, PrivEnum, Shard, SignificanceTest,
+        SortingAttr, TableStyle, ThousandsSeparator,
+    },
     counter::MaxCountUInt,
     time::TimerKind,
 };
@@ -20,14 +23,34 @@ pub(crate) fn command() -> Command {
     }
 
     // Custom arguments not supported by libtest:
+    // - arg
+    // - baseline
     // - bytes-format
+    // - child-cpu-time
+    // - depth
+    // - fastest-iter
+    // - filter-file
+    // - grid
+    // - grid-format
+    // - jobs
+    // - max-sample-time
+    // - name-width
+    // - rerun-failed
+    // - runs
     // - sample-count
+    // - save-baseline
     // - sample-size
+    // - shard
+    // - serializing-fences
+    // - sig-figs
+    // - significance-test
+    // - table-style
+    // - thousands-separator
+    // - time-budget
     // - timer
     // - sort
     // - sortr
-
-    // TODO: `--format <pretty|terse>`
+    // - warm-up-time
 
     Command::new("divan")
         .arg(
@@ -36,12 +59,25 @@ pub(crate) fn command() -> Command {
                 .help("Only run benchmarks whose names match this pattern")
                 .action(ArgAction::Append),
         )
+        .arg(
+            option("filter-file")
+                .value_name("PATH")
+                .help("Read filters from a file with one pattern per line, where '!pattern' skips")
+                .value_parser(value_parser!(std::path::PathBuf))
+                .action(ArgAction::Append),
+        )
         .arg(
             flag("test")
                 .help("Run benchmarks once to ensure they run successfully")
                 .conflicts_with("list"),
         )
         .arg(flag("list").help("Lists benchmarks").conflicts_with("test"))
+        .arg(
+            flag("calibrate")
+                .help("Measure Divan's own overheads on the current machine")
+                .conflicts_with_all(["test", "list"])
+                .hide(true),
+        )
         .arg(
             option("color")
                 .value_name("WHEN")
@@ -55,12 +91,80 @@ pub(crate) fn command() -> Command {
                 .action(ArgAction::Append),
         )
         .arg(flag("exact").help("Filter benchmarks by exact name rather than by pattern"))
+        .arg(
+            option("arg")
+                .value_name("VALUE")
+                .help("Only run benchmarks with an argument, const, or type named VALUE")
+                .action(ArgAction::Append),
+        )
+        .arg(flag("rerun-failed").help("Only run benchmarks that panicked or regressed in the last run"))
+        .arg(
+            option("shard")
+                .env("DIVAN_SHARD")
+                .value_name("INDEX/COUNT")
+                .help("Only run the INDEX-th of COUNT disjoint subsets of benchmarks, for splitting across machines")
+                .value_parser(value_parser!(Shard)),
+        )
+        .arg(
+            option("save-baseline")
+                .env("DIVAN_SAVE_BASELINE")
+                .value_name("NAME")
+                .help("Save sample times under a name for comparing against with '--baseline'"),
+        )
+        .arg(
+            option("baseline")
+                .env("DIVAN_BASELINE")
+                .value_name("NAME")
+                .help("Compare sample times against a baseline saved by '--save-baseline'"),
+        )
+        .arg(
+            option("significance-test")
+                .env("DIVAN_SIGNIFICANCE_TEST")
+                .value_name("ks|mann-whitney")
+                .help("Set the test for whether '--baseline' comparisons changed significantly")
+                .value_parser(value_parser!(PrivEnum<SignificanceTest>)),
+        )
         .arg(flag("ignored").help("Run only ignored benchmarks").conflicts_with("include-ignored"))
         .arg(
             flag("include-ignored")
                 .help("Run ignored and not-ignored benchmarks")
                 .conflicts_with("ignored"),
         )
+        .arg(
+            option("format")
+                .env("DIVAN_FORMAT")
+                .value_name("pretty|terse|json")
+                .help("Set the output format")
+                .value_parser(value_parser!(PrivEnum<OutputFormat>)),
+        )
+        .arg(
+            option("table-style")
+                .env("DIVAN_TABLE_STYLE")
+                .value_name("rounded|heavy|ascii|none")
+                .help("Set the characters used to draw the tree and table")
+                .value_parser(value_parser!(PrivEnum<TableStyle>)),
+        )
+        .arg(
+            option("grid")
+                .env("DIVAN_GRID")
+                .value_name("fastest|slowest|median|mean")
+                .help("Summarize benchmarks over both types and arguments in grids of a time statistic; benchmarks run with multiple thread counts are excluded")
+                .value_parser(value_parser!(PrivEnum<GridMetric>)),
+        )
+        .arg(
+            option("grid-format")
+                .env("DIVAN_GRID_FORMAT")
+                .value_name("table|markdown|csv")
+                .help("Set how '--grid' summaries are printed; only with '--format pretty'")
+                .value_parser(value_parser!(PrivEnum<GridFormat>)),
+        )
+        .arg(
+            option("thousands-separator")
+                .env("DIVAN_THOUSANDS_SEPARATOR")
+                .value_name("none|comma|underscore")
+                .help("Set the separator between groups of thousands in counts")
+                .value_parser(value_parser!(PrivEnum<ThousandsSeparator>)),
+        )
         .arg(
             option("sort")
                 .env("DIVAN_SORT")
@@ -97,6 +201,20 @@ pub(crate) fn command() -> Command {
                 .help("Set the number of iterations inside a single sample")
                 .value_parser(value_parser!(u32)),
         )
+        .arg(
+            option("runs")
+                .env("DIVAN_RUNS")
+                .value_name("N")
+                .help("Repeat the whole measurement of each benchmark N times and aggregate the results")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
+        .arg(
+            option("jobs")
+                .env("DIVAN_JOBS")
+                .value_name("N")
+                .help("Measure up to N benchmarks at a time, each pinned to a CPU, at the cost of interference between them")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
         .arg(
             option("threads")
                 .env("DIVAN_THREADS")
@@ -109,17 +227,46 @@ pub(crate) fn command() -> Command {
         .arg(
             option("min-time")
                 .env("DIVAN_MIN_TIME")
-                .value_name("SECS")
-                .help("Set the minimum seconds spent benchmarking a single function")
+                .value_name("DURATION")
+                .help("Set the minimum time spent benchmarking a single function, in seconds or with units like '2.5ms' and '1m30s'")
                 .value_parser(value_parser!(ParsedSeconds)),
         )
         .arg(
             option("max-time")
                 .env("DIVAN_MAX_TIME")
-                .value_name("SECS")
-                .help("Set the maximum seconds spent benchmarking a single function, with priority over '--min-time'")
+                .value_name("DURATION")
+                .help("Set the maximum time spent benchmarking a single function, with priority over '--min-time'")
+                .value_parser(value_parser!(ParsedSeconds)),
+        )
+        .arg(
+            option("max-sample-time")
+                .env("DIVAN_MAX_SAMPLE_TIME")
+                .value_name("DURATION")
+                .help("Set the maximum time of a single sample, after which fewer iterations are run per sample")
                 .value_parser(value_parser!(ParsedSeconds)),
         )
+        .arg(
+            option("time-budget")
+                .env("DIVAN_TIME_BUDGET")
+                .value_name("DURATION")
+                .help("Share a time budget for the whole run between benchmarks, giving more samples to noisier benchmarks")
+                .value_parser(value_parser!(ParsedSeconds)),
+        )
+        .arg(
+            option("warm-up-time")
+                .env("DIVAN_WARM_UP_TIME")
+                .value_name("DURATION")
+                .help("Spin the CPU for this long before sampling each benchmark, to bring it out of low-power states")
+                .value_parser(value_parser!(ParsedSeconds)),
+        )
+        .arg(
+            option("serializing-fences")
+                .env("DIVAN_SERIALIZING_FENCES")
+                .value_name("true|false")
+                .help("Emit serializing instructions (cpuid or isb) around sample timestamps")
+                .value_parser(value_parser!(bool))
+                .num_args(0..=1),
+        )
         .arg(
             option("skip-ext-time")
                 .env("DIVAN_SKIP_EXT_TIME")
@@ -128,6 +275,22 @@ pub(crate) fn command() -> Command {
                 .value_parser(value_parser!(bool))
                 .num_args(0..=1),
         )
+        .arg(
+            option("fastest-iter")
+                .env("DIVAN_FASTEST_ITER")
+                .value_name("true|false")
+                .help("Also time single iterations to report the fastest one")
+                .value_parser(value_parser!(bool))
+                .num_args(0..=1),
+        )
+        .arg(
+            option("child-cpu-time")
+                .env("DIVAN_CHILD_CPU_TIME")
+                .value_name("true|false")
+                .help("Also report the CPU time of child processes per iteration")
+                .value_parser(value_parser!(bool))
+                .num_args(0..=1),
+        )
         .arg(
             option("items-count")
                 .env("DIVAN_ITEMS_COUNT")
@@ -149,6 +312,27 @@ pub(crate) fn command() -> Command {
                 .value_name("decimal|binary")
                 .value_parser(value_parser!(crate::counter::PrivBytesFormat))
         )
+        .arg(
+            option("sig-figs")
+                .env("DIVAN_SIG_FIGS")
+                .value_name("N")
+                .help("Set the number of significant figures for durations and throughput in output")
+                .value_parser(value_parser!(u8).range(1..=16)),
+        )
+        .arg(
+            option("depth")
+                .env("DIVAN_DEPTH")
+                .value_name("N")
+                .help("Show N levels of the tree, collapsing deeper parents into their children's names")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
+        .arg(
+            option("name-width")
+                .env("DIVAN_NAME_WIDTH")
+                .value_name("N")
+                .help("Set the width of the name column, truncating the middle of longer names, and show all columns regardless of terminal width")
+                .value_parser(value_parser!(u32).range(1..)),
+        )
         .arg(
             option("chars-count")
                 .env("DIVAN_CHARS_COUNT")
@@ -169,6 +353,9 @@ impl ValueEnum for TimerKind {
         let name = match self {
             Self::Os => "os",
             Self::Tsc => "tsc",
+
+            // Only enabled via `Divan::mock_clock`.
+            Self::Mock => return None,
         };
         Some(PossibleValue::new(name))
     }
@@ -188,3 +375,102 @@ impl ValueEnum for SortingAttr {
         Some(PossibleValue::new(name))
     }
 }
+
+impl ValueEnum for PrivEnum<SignificanceTest> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self(SignificanceTest::Ks), Self(SignificanceTest::MannWhitney)]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self.0 {
+            SignificanceTest::Ks => "ks",
+            SignificanceTest::MannWhitney => "mann-whitney",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
+impl ValueEnum for PrivEnum<GridMetric> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self(GridMetric::Fastest),
+            Self(GridMetric::Slowest),
+            Self(GridMetric::Median),
+            Self(GridMetric::Mean),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.0.name()))
+    }
+}
+
+impl ValueEnum for PrivEnum<GridFormat> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self(GridFormat::Table), Self(GridFormat::Markdown), Self(GridFormat::Csv)]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self.0 {
+            GridFormat::Table => "table",
+            GridFormat::Markdown => "markdown",
+            GridFormat::Csv => "csv",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
+impl ValueEnum for PrivEnum<TableStyle> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self(TableStyle::Rounded),
+            Self(TableStyle::Heavy),
+            Self(TableStyle::Ascii),
+            Self(TableStyle::None),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self.0 {
+            TableStyle::Rounded => "rounded",
+            TableStyle::Heavy => "heavy",
+            TableStyle::Ascii => "ascii",
+            TableStyle::None => "none",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
+impl ValueEnum for PrivEnum<ThousandsSeparator> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self(ThousandsSeparator::None),
+            Self(ThousandsSeparator::Comma),
+            Self(ThousandsSeparator::Underscore),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self.0 {
+            ThousandsSeparator::None => "none",
+            ThousandsSeparator::Comma => "comma",
+            ThousandsSeparator::Underscore => "underscore",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
+
+impl ValueEnum for PrivEnum<OutputFormat> {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self(OutputFormat::Pretty), Self(OutputFormat::Terse), Self(OutputFormat::Json)]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let name = match self.0 {
+            OutputFormat::Pretty => "pretty",
+            OutputFormat::Terse => "terse",
+            OutputFormat::Json => "json",
+        };
+        Some(PossibleValue::new(name))
+    }
+}
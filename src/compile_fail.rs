@@ -24,6 +24,31 @@
 //! fn bench() {}
 //! ```
 //!
+//! ```compile_fail
+//! #[divan::bench(meta("a" = "x"), meta("b" = "y"))]
+//! fn bench() {}
+//! ```
+//!
+//! Keys repeated within `meta(...)` should also cause a compile error:
+//!
+//! ```compile_fail
+//! #[divan::bench(meta("a" = "x", "a" = "y"))]
+//! fn bench() {}
+//! ```
+//!
+//! Keys within `meta(...)` containing `=`, tabs, or line breaks should cause a
+//! compile error, since they would break line-based outputs:
+//!
+//! ```compile_fail
+//! #[divan::bench(meta("a=b" = "x"))]
+//! fn bench() {}
+//! ```
+//!
+//! ```compile_fail
+//! #[divan::bench(meta("a\tb" = "x"))]
+//! fn bench() {}
+//! ```
+//!
 //! # Type Checking
 //!
 //! The following won't produce any benchmarks because `types = []`. However, we
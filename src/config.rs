@@ -1,8 +1,11 @@
-use std::{cmp::Ordering, error::Error, str::FromStr, time::Duration};
+use std::{borrow::Cow, cmp::Ordering, error::Error, str::FromStr, time::Duration};
 
 use regex::Regex;
 
-/// `Duration` wrapper for parsing seconds from the CLI.
+use crate::time::FineDuration;
+
+/// `Duration` wrapper for parsing seconds or human-readable durations like
+/// "2.5ms" and "1m30s" from the CLI.
 #[derive(Clone, Copy)]
 pub(crate) struct ParsedSeconds(pub Duration);
 
@@ -10,7 +13,52 @@ impl FromStr for ParsedSeconds {
     type Err = Box<dyn Error + Send + Sync>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(Duration::try_from_secs_f64(f64::from_str(s)?)?))
+        if let Ok(secs) = f64::from_str(s) {
+            return Ok(Self(Duration::try_from_secs_f64(secs)?));
+        }
+
+        Ok(Self(FineDuration::from_str(s)?.try_into()?))
+    }
+}
+
+/// One of `count` disjoint subsets of benchmarks, for splitting a suite across
+/// machines with `--shard index/count`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Shard {
+    /// The 1-based index of this shard.
+    pub index: usize,
+
+    /// The total number of shards.
+    pub count: usize,
+}
+
+impl Shard {
+    /// Returns `true` if the benchmark at `position` in the sorted list of all
+    /// selected benchmark paths belongs to this shard.
+    ///
+    /// Benchmarks are assigned round-robin so that shards get similar numbers of
+    /// benchmarks from each module.
+    #[inline]
+    pub fn contains(self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("expected 'INDEX/COUNT' with 1 <= INDEX <= COUNT, got '{s}'");
+
+        let (index, count) = s.split_once('/').ok_or_else(error)?;
+        let index: usize = index.trim().parse().map_err(|_| error())?;
+        let count: usize = count.trim().parse().map_err(|_| error())?;
+
+        if index == 0 || index > count {
+            return Err(error());
+        }
+
+        Ok(Self { index, count })
     }
 }
 
@@ -26,6 +74,9 @@ pub(crate) enum Action {
 
     /// List benchmarks.
     List,
+
+    /// Benchmark Divan's own overheads.
+    Calibrate,
 }
 
 #[allow(dead_code)]
@@ -44,6 +95,233 @@ impl Action {
     pub fn is_list(&self) -> bool {
         matches!(self, Self::List)
     }
+
+    #[inline]
+    pub fn is_calibrate(&self) -> bool {
+        matches!(self, Self::Calibrate)
+    }
+}
+
+/// How benchmark results are printed.
+///
+/// See [`Divan::format`](crate::Divan::format).
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Tree with a table of statistics. This is the default.
+    #[default]
+    Pretty,
+
+    /// `full_path<TAB>median<TAB>throughput` on a single line per benchmark.
+    Terse,
+
+    /// A JSON object on a single line per benchmark. Only supported when
+    /// listing benchmarks.
+    Json,
+}
+
+/// Private wrapper of a public option enum that prevents leaking trait
+/// implementations we don't want to publicly commit to, such as
+/// `clap::ValueEnum`.
+#[derive(Clone, Copy)]
+pub(crate) struct PrivEnum<T>(pub T);
+
+/// The time statistic shown in summary grids of benchmarks over both types and
+/// arguments.
+///
+/// See [`Divan::grid`](crate::Divan::grid).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GridMetric {
+    /// The fastest sample.
+    Fastest,
+
+    /// The slowest sample.
+    Slowest,
+
+    /// The median sample.
+    Median,
+
+    /// The mean of all samples.
+    Mean,
+}
+
+impl GridMetric {
+    #[inline]
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Fastest => "fastest",
+            Self::Slowest => "slowest",
+            Self::Median => "median",
+            Self::Mean => "mean",
+        }
+    }
+}
+
+/// How summary grids are printed.
+///
+/// See [`Divan::grid_format`](crate::Divan::grid_format).
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GridFormat {
+    /// Aligned columns for reading in a terminal. This is the default.
+    #[default]
+    Table,
+
+    /// A Markdown table per benchmark.
+    Markdown,
+
+    /// Comma-separated values per benchmark, with times in nanoseconds.
+    Csv,
+}
+
+/// The test used to determine whether a benchmark changed significantly from a
+/// baseline.
+///
+/// See [`Divan::significance_test`](crate::Divan::significance_test).
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignificanceTest {
+    /// Two-sample Kolmogorov–Smirnov test, which detects any change in the
+    /// shape of the distribution. This is the default.
+    #[default]
+    Ks,
+
+    /// Mann–Whitney U test, which detects whether values from one sample tend
+    /// to be larger than the other.
+    MannWhitney,
+}
+
+/// Characters used to draw the tree and table of benchmark outputs.
+///
+/// See [`Divan::table_style`](crate::Divan::table_style).
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TableStyle {
+    /// Unicode box drawing with rounded corners, e.g. `╰─`. This is the
+    /// default.
+    #[default]
+    Rounded,
+
+    /// Heavy Unicode box drawing, e.g. `┗━`.
+    Heavy,
+
+    /// ASCII characters, e.g. `` `- ``, for terminals and log viewers that
+    /// mangle box drawing characters. Units such as `µs` are also written in
+    /// ASCII, e.g. `us`, and truncated names end their head with `...`.
+    Ascii,
+
+    /// Spaces instead of borders.
+    None,
+}
+
+impl TableStyle {
+    /// The branch to a child node.
+    pub(crate) fn branch(self, is_last: bool) -> &'static str {
+        match (self, is_last) {
+            (Self::Rounded, false) => "├─ ",
+            (Self::Rounded, true) => "╰─ ",
+            (Self::Heavy, false) => "┣━ ",
+            (Self::Heavy, true) => "┗━ ",
+            (Self::Ascii, false) => "|- ",
+            (Self::Ascii, true) => "`- ",
+            (Self::None, _) => "   ",
+        }
+    }
+
+    /// The line that continues past children to later siblings.
+    pub(crate) fn vertical(self) -> char {
+        match self {
+            Self::Rounded => '│',
+            Self::Heavy => '┃',
+            Self::Ascii => '|',
+            Self::None => ' ',
+        }
+    }
+
+    /// The prefix of the children of a node.
+    pub(crate) fn indent(self, is_last: bool) -> &'static str {
+        match (self, is_last) {
+            (Self::Rounded, false) => "│  ",
+            (Self::Heavy, false) => "┃  ",
+            (Self::Ascii, false) => "|  ",
+            (Self::None, false) | (_, true) => "   ",
+        }
+    }
+
+    /// The separator between table columns.
+    pub(crate) fn column_separator(self) -> &'static str {
+        match self {
+            Self::Rounded => " │ ",
+            Self::Heavy => " ┃ ",
+            Self::Ascii => " | ",
+            Self::None => "   ",
+        }
+    }
+
+    /// The replacement for the middle of truncated names.
+    pub(crate) fn ellipsis(self) -> &'static str {
+        match self {
+            Self::Ascii => "...",
+            _ => "…",
+        }
+    }
+
+    /// Returns `text` with the `µ` of `µs` replaced by `u` if only ASCII
+    /// should be printed. Both are one character wide, so alignment is kept.
+    pub(crate) fn units(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Self::Ascii if text.contains('µ') => Cow::Owned(text.replace('µ', "u")),
+            _ => Cow::Borrowed(text),
+        }
+    }
+}
+
+/// The separator between groups of thousands in counts, such as of
+/// iterations.
+///
+/// See [`Divan::thousands_separator`](crate::Divan::thousands_separator).
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ThousandsSeparator {
+    /// `1000000`. This is the default.
+    #[default]
+    None,
+
+    /// `1,000,000`.
+    Comma,
+
+    /// `1_000_000`.
+    Underscore,
+}
+
+impl ThousandsSeparator {
+    #[inline]
+    pub(crate) fn char(self) -> Option<char> {
+        match self {
+            Self::None => None,
+            Self::Comma => Some(','),
+            Self::Underscore => Some('_'),
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl OutputFormat {
+    #[inline]
+    pub(crate) fn is_pretty(self) -> bool {
+        matches!(self, Self::Pretty)
+    }
+
+    #[inline]
+    pub(crate) fn is_terse(self) -> bool {
+        matches!(self, Self::Terse)
+    }
+
+    #[inline]
+    pub(crate) fn is_json(self) -> bool {
+        matches!(self, Self::Json)
+    }
 }
 
 /// Filters which benchmark to run based on name.
@@ -62,6 +340,30 @@ impl Filter {
     }
 }
 
+/// A pattern read from a `--filter-file`.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum FilterFileLine<'a> {
+    /// Only run benchmarks matching this pattern.
+    Include(&'a str),
+
+    /// Skip benchmarks matching this pattern, written as `!pattern`.
+    Exclude(&'a str),
+}
+
+impl<'a> FilterFileLine<'a> {
+    /// Parses one pattern per line, ignoring blank lines and `#` comments.
+    pub fn parse_all(contents: &'a str) -> impl Iterator<Item = Self> {
+        contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| match line.strip_prefix('!') {
+                Some(pattern) => Self::Exclude(pattern.trim_start()),
+                None => Self::Include(line),
+            })
+    }
+}
+
 /// How to treat benchmarks based on whether they're marked as `#[ignore]`.
 #[derive(Copy, Clone, Default)]
 pub(crate) enum RunIgnored {
@@ -182,3 +484,69 @@ impl SortingAttr {
         Ordering::Equal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_filter_file() {
+        let contents = "
+            # Hashing benchmarks.
+            hash::
+
+            !hash::slow
+            ! ^collections::vec::default$\r
+            sort
+        ";
+
+        let lines: Vec<FilterFileLine> = FilterFileLine::parse_all(contents).collect();
+
+        assert_eq!(
+            lines,
+            [
+                FilterFileLine::Include("hash::"),
+                FilterFileLine::Exclude("hash::slow"),
+                FilterFileLine::Exclude("^collections::vec::default$"),
+                FilterFileLine::Include("sort"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_shard() {
+        assert_eq!("2/4".parse(), Ok(Shard { index: 2, count: 4 }));
+        assert_eq!("1/1".parse(), Ok(Shard { index: 1, count: 1 }));
+
+        for invalid in ["", "2", "0/4", "5/4", "1/0", "a/4", "2/b", "1/2/3"] {
+            assert!(invalid.parse::<Shard>().is_err(), "{invalid:?}");
+        }
+    }
+
+    #[test]
+    fn shard_contains() {
+        let shards = [1, 2, 3].map(|index| Shard { index, count: 3 });
+
+        // Every position belongs to exactly one shard.
+        for position in 0..10 {
+            assert_eq!(shards.iter().filter(|shard| shard.contains(position)).count(), 1);
+        }
+
+        assert!(shards[0].contains(0));
+        assert!(shards[1].contains(4));
+        assert!(shards[2].contains(8));
+    }
+
+    /// The tree painter relies on prefixes being 3 characters per level.
+    #[test]
+    fn table_style_widths() {
+        for style in [TableStyle::Rounded, TableStyle::Heavy, TableStyle::Ascii, TableStyle::None] {
+            for is_last in [false, true] {
+                assert_eq!(style.branch(is_last).chars().count(), 3);
+                assert_eq!(style.indent(is_last).chars().count(), 3);
+            }
+
+            assert_eq!(style.column_separator().chars().count(), 3);
+        }
+    }
+}
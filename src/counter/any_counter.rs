@@ -1,7 +1,9 @@
 use std::any::TypeId;
 
 use crate::{
-    counter::{BytesCount, BytesFormat, CharsCount, IntoCounter, ItemsCount, MaxCountUInt},
+    counter::{
+        BytesCount, BytesFormat, CharsCount, IntoCounter, ItemsCount, LinesCount, MaxCountUInt,
+    },
     time::FineDuration,
     util::{self, fmt::DisplayThroughput},
 };
@@ -27,6 +29,8 @@ impl AnyCounter {
             Self::chars(chars.count)
         } else if let Some(items) = util::cast_ref::<ItemsCount>(&counter) {
             Self::items(items.count)
+        } else if let Some(lines) = util::cast_ref::<LinesCount>(&counter) {
+            Self::lines(lines.count)
         } else {
             unreachable!()
         }
@@ -52,11 +56,16 @@ impl AnyCounter {
         Self::known(KnownCounterKind::Items, count)
     }
 
+    #[inline]
+    pub(crate) fn lines(count: MaxCountUInt) -> Self {
+        Self::known(KnownCounterKind::Lines, count)
+    }
+
     pub(crate) fn display_throughput(
         &self,
         duration: FineDuration,
         bytes_format: BytesFormat,
-    ) -> DisplayThroughput {
+    ) -> DisplayThroughput<'_> {
         DisplayThroughput { counter: self, picos: duration.picos as f64, bytes_format }
     }
 
@@ -77,12 +86,13 @@ pub(crate) enum KnownCounterKind {
     Bytes,
     Chars,
     Items,
+    Lines,
 }
 
 impl KnownCounterKind {
-    pub const COUNT: usize = 3;
+    pub const COUNT: usize = 4;
 
-    pub const ALL: [Self; Self::COUNT] = [Self::Bytes, Self::Chars, Self::Items];
+    pub const ALL: [Self; Self::COUNT] = [Self::Bytes, Self::Chars, Self::Items, Self::Lines];
 
     /// The maximum width for columns displaying counters.
     pub const MAX_COMMON_COLUMN_WIDTH: usize = "1.111 Kitem/s".len();
@@ -96,6 +106,8 @@ impl KnownCounterKind {
             Self::Chars
         } else if id == TypeId::of::<ItemsCount>() {
             Self::Items
+        } else if id == TypeId::of::<LinesCount>() {
+            Self::Lines
         } else {
             unreachable!()
         }
@@ -183,5 +195,26 @@ mod tests {
             test(0, 1, "0 item/s");
             test(0, u128::MAX, "0 item/s");
         }
+
+        #[test]
+        fn lines() {
+            #[track_caller]
+            fn test(lines: MaxCountUInt, picos: u128, expected: &str) {
+                assert_eq!(
+                    AnyCounter::lines(lines)
+                        .display_throughput(FineDuration { picos }, BytesFormat::default())
+                        .to_string(),
+                    expected
+                );
+            }
+
+            test(1, 0, "inf line/s");
+            test(MaxCountUInt::MAX, 0, "inf line/s");
+
+            test(0, 0, "0 line/s");
+            test(0, 1, "0 line/s");
+            test(0, u128::MAX, "0 line/s");
+            test(3, 1_000_000_000, "3 Kline/s");
+        }
     }
 }
@@ -32,12 +32,26 @@ impl CounterCollection {
         &self.info(counter_kind).counts
     }
 
-    pub(crate) fn mean_count(&self, counter_kind: KnownCounterKind) -> MaxCountUInt {
+    /// Returns the mean count of `counter_kind`, where input counts are only
+    /// included for samples whose index is accepted.
+    pub(crate) fn mean_count(
+        &self,
+        counter_kind: KnownCounterKind,
+        is_accepted_index: impl Fn(usize) -> bool,
+    ) -> MaxCountUInt {
         let counts = self.counts(counter_kind);
 
-        let sum: u128 = counts.iter().map(|&c| c as u128).sum();
+        let (sum, len) = if self.uses_input_counts(counter_kind) {
+            counts
+                .iter()
+                .enumerate()
+                .filter(|&(index, _)| is_accepted_index(index))
+                .fold((0u128, 0u128), |(sum, len), (_, &c)| (sum + c as u128, len + 1))
+        } else {
+            (counts.iter().map(|&c| c as u128).sum(), counts.len() as u128)
+        };
 
-        (sum / counts.len() as u128) as MaxCountUInt
+        sum.checked_div(len).unwrap_or_default() as MaxCountUInt
     }
 
     #[inline]
@@ -74,13 +74,24 @@ pub struct ItemsCount {
     count: MaxCountUInt,
 }
 
+/// Process N lines of text.
+///
+/// This is beneficial for benchmarking line-oriented parsers, such as for logs
+/// or CSV, where lines per second is the most meaningful throughput.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LinesCount {
+    count: MaxCountUInt,
+}
+
 impl Sealed for BytesCount {}
 impl Sealed for CharsCount {}
 impl Sealed for ItemsCount {}
+impl Sealed for LinesCount {}
 
 impl Counter for BytesCount {}
 impl Counter for CharsCount {}
 impl Counter for ItemsCount {}
+impl Counter for LinesCount {}
 
 impl<C: AsCountUInt> From<C> for BytesCount {
     #[inline]
@@ -103,6 +114,13 @@ impl<C: AsCountUInt> From<C> for ItemsCount {
     }
 }
 
+impl<C: AsCountUInt> From<C> for LinesCount {
+    #[inline]
+    fn from(count: C) -> Self {
+        Self::new(count.as_max_uint())
+    }
+}
+
 impl BytesCount {
     /// Count N bytes.
     #[inline]
@@ -137,6 +155,10 @@ impl BytesCount {
     }
 
     /// Counts the bytes of [`Iterator::Item`s](Iterator::Item).
+    ///
+    /// This counts the size of each item itself. To instead count the bytes
+    /// referenced by items, such as in nested collections, use
+    /// [`BytesCount::of_slices`] or [`BytesCount::of_strs`].
     #[inline]
     pub fn of_iter<T, I>(iter: I) -> Self
     where
@@ -166,6 +188,69 @@ impl BytesCount {
     pub fn of_slice<T, S: ?Sized + AsRef<[T]>>(s: &S) -> Self {
         Self::of_val(s.as_ref())
     }
+
+    /// Counts the total bytes of [slices](prim@slice) produced by an
+    /// [`Iterator`].
+    ///
+    /// This is convenient for nested collections like [`Vec<Vec<T>>`](Vec):
+    ///
+    /// ```
+    /// use divan::counter::BytesCount;
+    ///
+    /// let chunks: Vec<Vec<u8>> = vec![vec![0; 10], vec![0; 20]];
+    ///
+    /// assert_eq!(BytesCount::of_slices(&chunks), BytesCount::new(30u8));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total overflows [`u64`] (or [`usize`] if larger).
+    #[inline]
+    pub fn of_slices<T, S, I>(iter: I) -> Self
+    where
+        S: AsRef<[T]>,
+        I: IntoIterator<Item = S>,
+    {
+        Self::of_vals(iter.into_iter().map(|s| mem::size_of_val(s.as_ref())))
+    }
+
+    /// Counts the total bytes of [`&str`s](prim@str) produced by an
+    /// [`Iterator`].
+    ///
+    /// This is convenient for collections of strings, such as lines of a file:
+    ///
+    /// ```
+    /// use divan::counter::BytesCount;
+    ///
+    /// let lines = "hello\nworld\n".lines();
+    ///
+    /// assert_eq!(BytesCount::of_strs(lines), BytesCount::new(10u8));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total overflows [`u64`] (or [`usize`] if larger).
+    #[inline]
+    pub fn of_strs<S, I>(iter: I) -> Self
+    where
+        S: AsRef<str>,
+        I: IntoIterator<Item = S>,
+    {
+        Self::of_vals(iter.into_iter().map(|s| s.as_ref().len()))
+    }
+
+    /// Sums byte sizes.
+    #[inline]
+    fn of_vals(sizes: impl Iterator<Item = usize>) -> Self {
+        let count = sizes.fold(0 as MaxCountUInt, |total, size| {
+            match total.checked_add(size as MaxCountUInt) {
+                Some(total) => total,
+                None => panic!("overflow"),
+            }
+        });
+
+        Self { count }
+    }
 }
 
 macro_rules! type_bytes {
@@ -229,6 +314,41 @@ impl ItemsCount {
     }
 }
 
+impl LinesCount {
+    /// Count N lines.
+    #[inline]
+    pub fn new<N: CountUInt>(count: N) -> Self {
+        Self { count: count.into_max_uint() }
+    }
+
+    /// Counts the [lines](str::lines) of a [`&str`](prim@str).
+    ///
+    /// Lines are terminated by `\n` or `\r\n`, and a trailing line ending does
+    /// not start an extra empty line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use divan::{Bencher, counter::LinesCount};
+    ///
+    /// #[divan::bench]
+    /// fn parse_csv(bencher: Bencher) {
+    ///     let csv: &str = // ...
+    ///     # "";
+    ///
+    ///     bencher
+    ///         .counter(LinesCount::of_str(csv))
+    ///         .bench(|| {
+    ///             divan::black_box(csv).lines().map(|line| line.split(',').count()).sum::<usize>()
+    ///         });
+    /// }
+    /// ```
+    #[inline]
+    pub fn of_str<S: ?Sized + AsRef<str>>(s: &S) -> Self {
+        Self::new(s.as_ref().lines().count())
+    }
+}
+
 /// The numerical base for [`BytesCount`] in benchmark outputs.
 ///
 /// See [`Divan::bytes_format`](crate::Divan::bytes_format) for more info.
@@ -266,6 +386,24 @@ impl clap::ValueEnum for PrivBytesFormat {
 mod tests {
     use super::*;
 
+    mod lines_count {
+        use super::*;
+
+        #[test]
+        fn of_str() {
+            #[track_caller]
+            fn test(s: &str, expected: u8) {
+                assert_eq!(LinesCount::of_str(s), LinesCount::new(expected));
+            }
+
+            test("", 0);
+            test("a", 1);
+            test("a\n", 1);
+            test("a\r\nb", 2);
+            test("a\n\nb\n", 3);
+        }
+    }
+
     mod bytes_count {
         use super::*;
 
@@ -273,5 +411,22 @@ mod tests {
         fn of_iter() {
             assert_eq!(BytesCount::of_iter::<i32, _>([1, 2, 3]), BytesCount::of_slice(&[1, 2, 3]));
         }
+
+        #[test]
+        fn of_slices() {
+            let nested: Vec<Vec<i32>> = vec![vec![1, 2, 3], vec![], vec![4]];
+            assert_eq!(BytesCount::of_slices(&nested), BytesCount::i32(4));
+
+            let slices: [&[u16]; 2] = [&[1, 2], &[3]];
+            assert_eq!(BytesCount::of_slices(slices), BytesCount::u16(3));
+        }
+
+        #[test]
+        fn of_strs() {
+            let strings = vec![String::from("héllo"), String::new(), String::from("!")];
+            assert_eq!(BytesCount::of_strs(&strings), BytesCount::new(7u8));
+
+            assert_eq!(BytesCount::of_strs(["a", "bc"]), BytesCount::new(3u8));
+        }
     }
 }
@@ -1,20 +1,42 @@
 #![allow(clippy::too_many_arguments)]
 
-use std::{borrow::Cow, cell::RefCell, fmt, num::NonZeroUsize, time::Duration};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Reverse,
+    collections::HashMap,
+    fmt, fs,
+    num::{NonZeroU64, NonZeroUsize},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering::Relaxed},
+        Mutex, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 use clap::ColorChoice;
 use regex::Regex;
 
 use crate::{
-    bench::BenchOptions,
-    config::{Action, Filter, ParsedSeconds, RunIgnored, SortingAttr},
+    baseline::Baseline,
+    bench::{BenchContext, BenchOptions},
+    budget::RunBudget,
+    config::{
+        Action, Filter, FilterFileLine, GridFormat, GridMetric, OutputFormat, ParsedSeconds,
+        PrivEnum, RunIgnored, Shard, SignificanceTest, SortingAttr, TableStyle, ThousandsSeparator,
+    },
     counter::{
         BytesCount, BytesFormat, CharsCount, IntoCounter, ItemsCount, MaxCountUInt, PrivBytesFormat,
     },
-    entry::{AnyBenchEntry, BenchEntryRunner, EntryTree},
+    entry::{AnyBenchEntry, BenchEntryRunner, EntryInfo, EntryOptions, EntryTree},
+    progress::Progress,
+    stats::{Comparison, RunVariance, Stats},
     time::{FineDuration, Timer, TimerKind},
     tree_painter::{TreeColumn, TreePainter},
-    util, Bencher,
+    util, Bencher, Samples,
 };
 
 /// The benchmark runner.
@@ -22,14 +44,35 @@ use crate::{
 pub struct Divan {
     action: Action,
     timer: TimerKind,
+    serializing_fences: bool,
     reverse_sort: bool,
     sorting_attr: SortingAttr,
     color: ColorChoice,
+    format: OutputFormat,
+    table_style: TableStyle,
+    thousands_separator: ThousandsSeparator,
     bytes_format: BytesFormat,
+    sig_figs: Option<usize>,
+    depth: Option<usize>,
+    name_width: Option<usize>,
+    runs: Option<usize>,
+    jobs: Option<usize>,
+    time_budget: Option<Duration>,
+    features: Option<Vec<String>>,
     filters: Vec<Filter>,
     skip_filters: Vec<Filter>,
+    arg_filters: Vec<String>,
     run_ignored: RunIgnored,
+    rerun_failed: bool,
+    shard: Option<Shard>,
+    save_baseline: Option<String>,
+    baseline: Option<String>,
+    significance_test: SignificanceTest,
+    grid_metric: Option<GridMetric>,
+    grid_format: GridFormat,
     bench_options: BenchOptions<'static>,
+    configure_entries: Option<Box<dyn Fn(EntryInfo, &mut EntryOptions) + Send + Sync>>,
+    on_samples: Option<Box<dyn Fn(&Samples) + Send + Sync>>,
 }
 
 /// Immutable context shared between entry runs.
@@ -40,10 +83,50 @@ pub(crate) struct SharedContext {
     /// The timer used to measure samples.
     pub timer: Timer,
 
+    /// Whether to emit serializing instructions around sample timestamps.
+    pub serializing_fences: bool,
+
     /// Per-iteration overhead.
     ///
     /// `min_time` and `max_time` do not consider this as benchmarking time.
     pub bench_overhead: FineDuration,
+
+    /// Sample times from a previous run to compare against.
+    pub baseline: Option<Baseline>,
+
+    /// Estimated time remaining for the run.
+    pub progress: Progress,
+
+    /// Time budget shared by all benchmarks of the run, if set.
+    pub budget: Option<RunBudget>,
+
+    /// Measurements of benchmarks that ran before `run_tree` reached them, for
+    /// `priority` or `--jobs`, keyed by path.
+    pub early_results: Mutex<HashMap<String, Measurement>>,
+}
+
+/// The measurement of a benchmark over all runs.
+pub(crate) struct Measurement {
+    run_stats: Vec<Stats>,
+    run_durations: Vec<Vec<FineDuration>>,
+
+    /// Samples of each run, if passed to `Divan::on_samples`.
+    run_samples: Vec<Samples>,
+
+    /// Time spent measuring, including sample size tuning and overhead.
+    wall_time: FineDuration,
+}
+
+/// A benchmark to measure before `run_tree` reaches it.
+struct EarlyJob<'a> {
+    runner: BenchEntryRunner,
+    arg_index: Option<usize>,
+    thread_count: NonZeroUsize,
+    priority: u32,
+    options: BenchOptions<'static>,
+    display_name: &'a str,
+    bench_path: String,
+    leaf_path: String,
 }
 
 impl fmt::Debug for Divan {
@@ -98,6 +181,13 @@ impl Divan {
     }
 
     pub(crate) fn run_action(&self, action: Action) {
+        if action.is_calibrate() {
+            self.calibrate();
+            return;
+        }
+
+        let start_time = Instant::now();
+
         let mut tree: Vec<EntryTree> = if cfg!(miri) {
             // Miri does not work with our linker tricks.
             Vec::new()
@@ -122,9 +212,76 @@ impl Divan {
             tree
         };
 
+        // Only rerun entries that failed in the last run.
+        let failed_entries: Option<Vec<String>> =
+            if self.rerun_failed && !action.is_list() { Some(crate::failed::load()) } else { None };
+
+        if let Some(failed_entries) = &failed_entries {
+            if failed_entries.is_empty() {
+                eprintln!("No failed benchmarks were recorded in the last run");
+                return;
+            }
+        }
+
         // Filter after inserting groups so that we can properly use groups'
         // display names.
-        EntryTree::retain(&mut tree, |entry_path| self.filter(entry_path));
+        EntryTree::retain(&mut tree, |entry_path| {
+            self.filter(entry_path)
+                && failed_entries
+                    .as_ref()
+                    .map_or(true, |failed| failed.iter().any(|f| f == entry_path))
+        });
+
+        if !self.arg_filters.is_empty() {
+            EntryTree::retain_params(&mut tree, &|name| {
+                self.arg_filters.iter().any(|filter| filter == name)
+            });
+        }
+
+        // Shard after filtering so that shards split only the selected
+        // benchmarks. Paths are sorted so that every machine running the same
+        // benchmarks agrees on the assignment.
+        if let Some(shard) = self.shard {
+            let mut paths = Vec::new();
+            EntryTree::retain(&mut tree, |entry_path| {
+                paths.push(entry_path.to_owned());
+                true
+            });
+            paths.sort_unstable();
+
+            EntryTree::retain(&mut tree, |entry_path| {
+                paths
+                    .binary_search_by(|path| path.as_str().cmp(entry_path))
+                    .is_ok_and(|position| shard.contains(position))
+            });
+        }
+
+        // Failures are only persisted for the last run of each benchmark.
+        if !action.is_list() {
+            let mut paths = Vec::new();
+            EntryTree::retain(&mut tree, |entry_path| {
+                paths.push(entry_path.to_owned());
+                true
+            });
+
+            crate::failed::start_recording(|path| paths.iter().any(|p| p == path));
+        }
+
+        // Load before saving in case both refer to the same baseline.
+        let baseline = match &self.baseline {
+            Some(name) if action.is_bench() => crate::baseline::load(name),
+            _ => None,
+        };
+
+        if let Some(name) = &self.save_baseline {
+            if action.is_bench() {
+                crate::baseline::start_recording(name);
+
+                if let Some(features) = &self.features {
+                    crate::baseline::record_features(name, features);
+                }
+            }
+        }
 
         // Quick exit without doing unnecessary work.
         if tree.is_empty() {
@@ -134,32 +291,63 @@ impl Divan {
         // Sorting is after filtering to compare fewer elements.
         EntryTree::sort_by_attr(&mut tree, self.sorting_attr, self.reverse_sort);
 
-        let timer = match self.timer {
-            TimerKind::Os => Timer::Os,
+        let timer = self.get_timer();
 
-            TimerKind::Tsc => {
-                match Timer::get_tsc() {
-                    Ok(tsc) => tsc,
-                    Err(error) => {
-                        eprintln!("warning: CPU timestamp counter is unavailable ({error}), defaulting to OS");
-                        Timer::Os
-                    }
+        if action.is_bench() {
+            let precision = timer.precision().to_string();
+            eprintln!("Timer precision: {}", self.table_style.units(&precision));
+
+            if let Some(frequency) = timer.tsc_frequency() {
+                eprintln!("Timer frequency: {}", fmt_frequency(frequency));
+
+                if let Some(name) = &self.save_baseline {
+                    crate::baseline::record_tsc_frequency(name, frequency);
                 }
             }
-        };
 
-        if action.is_bench() {
-            eprintln!("Timer precision: {}", timer.precision());
+            if let Some(features) = &self.features {
+                eprintln!("Features: {}", fmt_features(Some(features)));
+            }
+
+            if let (Some(name), Some(baseline)) = (&self.baseline, &baseline) {
+                let baseline_features = baseline.features();
+
+                if self.features.is_some() || baseline_features.is_some() {
+                    eprintln!(
+                        "Comparing against baseline '{name}' with features: {}",
+                        fmt_features(baseline_features),
+                    );
+                }
+
+                if let Some(frequency) = baseline.tsc_frequency() {
+                    eprintln!("Baseline '{name}' timer frequency: {}", fmt_frequency(frequency));
+                }
+            }
         }
 
         let shared_context = SharedContext {
             action,
             timer,
+            serializing_fences: self.serializing_fences,
             bench_overhead: if action.is_bench() {
                 timer.measure_sample_loop_overhead()
             } else {
                 FineDuration::default()
             },
+            baseline,
+            progress: if action.is_bench() && self.format.is_pretty() && self.get_jobs() == 1 {
+                let bench_count =
+                    EntryTree::bench_count(&tree, self.bench_options.threads.as_deref());
+                Progress::new(bench_count, self.table_style)
+            } else {
+                Progress::default()
+            },
+            budget: self.time_budget.filter(|_| action.is_bench()).map(|total| {
+                let bench_count =
+                    EntryTree::bench_count(&tree, self.bench_options.threads.as_deref());
+                RunBudget::new(total, bench_count * self.get_runs(), self.get_jobs())
+            }),
+            early_results: Mutex::default(),
         };
 
         let column_widths = if action.is_bench() {
@@ -167,6 +355,8 @@ impl Divan {
                 if column.is_last() {
                     // The last column doesn't use padding.
                     0
+                } else if column.is_time_stat() {
+                    EntryTree::common_column_width(&tree, column) + self.extra_sig_figs()
                 } else {
                     EntryTree::common_column_width(&tree, column)
                 }
@@ -175,18 +365,199 @@ impl Divan {
             [0; TreeColumn::COUNT]
         };
 
-        let tree_painter =
-            RefCell::new(TreePainter::new(EntryTree::max_name_span(&tree, 0), column_widths));
+        let tree_painter = RefCell::new(TreePainter::new(
+            EntryTree::max_name_span(
+                &tree,
+                self.get_depth(),
+                self.bench_options.threads.as_deref(),
+            ),
+            column_widths,
+            self.format,
+            self.table_style,
+            self.get_sig_figs(),
+            self.get_depth(),
+            self.name_width,
+        ));
+
+        if action.is_bench() {
+            let mut tree_painter = tree_painter.borrow_mut();
+
+            tree_painter.set_thousands_separator(self.thousands_separator.char());
+            tree_painter.set_tsc_frequency(timer.tsc_frequency());
+
+            if let Some(metric) = self.grid_metric {
+                tree_painter.set_grid_metric(metric);
+            }
+
+            // A fixed name width opts out of fitting the terminal.
+            if let Some(width) = util::terminal_width().filter(|_| self.name_width.is_none()) {
+                tree_painter.fit_width(width);
+            }
+        }
+
+        if action.is_bench() {
+            self.run_early(&tree, &shared_context);
+        }
+
+        self.run_tree(action, &tree, "", &shared_context, None, &[], &tree_painter);
+
+        if action.is_bench() {
+            let tree_painter = tree_painter.borrow();
+            tree_painter.finish(start_time.elapsed().into(), self.bytes_format);
+            tree_painter.finish_grids(self.grid_format);
+        }
+
+        // Exit with the same code as failed `cargo test` runs, without another
+        // panic message after the failures that were already printed.
+        if action.is_test() && !tree_painter.borrow().finish_test() {
+            std::process::exit(101);
+        }
+    }
+
+    /// Saves the benchmark's sample times if `--save-baseline` is set and
+    /// compares them against the baseline if `--baseline` is set.
+    fn save_and_compare(
+        &self,
+        shared_context: &SharedContext,
+        iter_durations: &[FineDuration],
+        bench_path: &str,
+        custom_meta: &[(&str, &str)],
+    ) -> Option<Comparison> {
+        if let Some(name) = &self.save_baseline {
+            crate::baseline::record(name, bench_path, iter_durations, custom_meta);
+        }
+
+        let baseline = shared_context.baseline.as_ref()?.get(bench_path)?;
+        Comparison::new(iter_durations, baseline, self.significance_test)
+    }
+
+    fn get_runs(&self) -> usize {
+        self.runs.unwrap_or(1).max(1)
+    }
+
+    fn get_jobs(&self) -> usize {
+        self.jobs.unwrap_or(1).max(1)
+    }
+
+    fn get_depth(&self) -> usize {
+        self.depth.unwrap_or(usize::MAX).max(1)
+    }
+
+    fn get_sig_figs(&self) -> usize {
+        self.sig_figs.unwrap_or(util::fmt::DEFAULT_SIG_FIGS)
+    }
+
+    /// The number of characters by which to widen time and throughput columns.
+    fn extra_sig_figs(&self) -> usize {
+        self.get_sig_figs().saturating_sub(util::fmt::DEFAULT_SIG_FIGS)
+    }
+
+    fn get_timer(&self) -> Timer {
+        match self.timer {
+            TimerKind::Os => Timer::Os,
+            TimerKind::Mock => Timer::Mock,
+
+            TimerKind::Tsc => {
+                match Timer::get_tsc() {
+                    Ok(tsc) => tsc,
+                    Err(error) => {
+                        eprintln!("warning: CPU timestamp counter is unavailable ({error}), defaulting to OS");
+                        Timer::Os
+                    }
+                }
+            }
+        }
+    }
+
+    /// Benchmarks the overheads of Divan's internals as if they were
+    /// registered benchmarks, to help interpret very fast results.
+    fn calibrate(&self) {
+        use crate::time::Timestamp;
+
+        let timer = self.get_timer();
+        let timer_kind = timer.kind();
+        let bench_overhead = timer.measure_sample_loop_overhead();
+
+        let style = self.table_style;
+        eprintln!("Timer precision: {}", style.units(&timer.precision().to_string()));
+        if let Some(frequency) = timer.tsc_frequency() {
+            eprintln!("Timer frequency: {}", fmt_frequency(frequency));
+        }
+        eprintln!("Sample loop overhead: {}", style.units(&bench_overhead.to_string()));
+
+        let shared_context = SharedContext {
+            action: Action::Bench,
+            timer,
+            serializing_fences: self.serializing_fences,
+            bench_overhead,
+            baseline: None,
+            progress: Progress::default(),
+            budget: None,
+            early_results: Mutex::default(),
+        };
+
+        let calibrations: [(&str, &dyn Fn(Bencher)); 4] = [
+            ("empty_closure", &|bencher| bencher.bench(|| {})),
+            ("black_box", &|bencher| bencher.bench(|| crate::black_box_drop(crate::black_box(0)))),
+            // `String` needs to be dropped, so outputs are deferred.
+            ("drop_store", &|bencher| bencher.bench(String::new)),
+            ("timer", &|bencher| bencher.bench(|| Timestamp::start(timer_kind))),
+        ];
+
+        let column_widths = TreeColumn::ALL.map(|column| {
+            if column.is_time_stat() {
+                crate::counter::KnownCounterKind::MAX_COMMON_COLUMN_WIDTH + self.extra_sig_figs()
+            } else {
+                0
+            }
+        });
+
+        let name = "calibrate";
+        let max_name_span = calibrations
+            .iter()
+            .map(|(name, _)| 3 + name.chars().count())
+            .fold(name.len(), usize::max);
+
+        let mut tree_painter = TreePainter::new(
+            max_name_span,
+            column_widths,
+            self.format,
+            self.table_style,
+            self.get_sig_figs(),
+            usize::MAX,
+            None,
+        );
+        tree_painter.start_parent(name, true);
+
+        for (i, (name, calibration)) in calibrations.iter().enumerate() {
+            let is_last = i == calibrations.len() - 1;
+
+            tree_painter.start_leaf(name, is_last);
+
+            let mut bench_context =
+                BenchContext::new(&shared_context, &self.bench_options, NonZeroUsize::MIN);
+            calibration(Bencher::new(&mut bench_context));
+
+            tree_painter.finish_leaf(
+                is_last,
+                &bench_context.compute_stats(),
+                None,
+                &[],
+                self.bytes_format,
+            );
+        }
 
-        self.run_tree(action, &tree, &shared_context, None, &tree_painter);
+        tree_painter.finish_parent();
     }
 
     fn run_tree(
         &self,
         action: Action,
         tree: &[EntryTree],
+        parent_path: &str,
         shared_context: &SharedContext,
         parent_options: Option<&BenchOptions>,
+        parent_meta: &[(&'static str, &'static str)],
         tree_painter: &RefCell<TreePainter>,
     ) {
         for (i, child) in tree.iter().enumerate() {
@@ -194,6 +565,12 @@ impl Divan {
 
             let name = child.display_name();
 
+            let path = if parent_path.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{parent_path}::{name}")
+            };
+
             let child_options = child.bench_options();
 
             // Overwrite `parent_options` with `child_options` if applicable.
@@ -207,20 +584,47 @@ impl Divan {
                 }
             };
 
+            // Extend `parent_meta` with the child's, which takes priority.
+            let custom_meta: Vec<(&'static str, &'static str)>;
+            let custom_meta = match child.meta().map(|meta| meta.custom_meta) {
+                None | Some([]) => parent_meta,
+                Some(child_meta) => {
+                    custom_meta = parent_meta
+                        .iter()
+                        .filter(|(key, _)| {
+                            !child_meta.iter().any(|(child_key, _)| child_key == key)
+                        })
+                        .chain(child_meta)
+                        .copied()
+                        .collect();
+                    &custom_meta
+                }
+            };
+
             match child {
                 EntryTree::Leaf { entry, args } => self.run_bench_entry(
                     action,
                     *entry,
+                    &path,
                     args.as_deref(),
                     shared_context,
                     options,
+                    custom_meta,
                     tree_painter,
                     is_last,
                 ),
                 EntryTree::Parent { children, .. } => {
                     tree_painter.borrow_mut().start_parent(name, is_last);
 
-                    self.run_tree(action, children, shared_context, options, tree_painter);
+                    self.run_tree(
+                        action,
+                        children,
+                        &path,
+                        shared_context,
+                        options,
+                        custom_meta,
+                        tree_painter,
+                    );
 
                     tree_painter.borrow_mut().finish_parent();
                 }
@@ -232,27 +636,34 @@ impl Divan {
         &self,
         action: Action,
         bench_entry: AnyBenchEntry,
+        entry_path: &str,
         bench_arg_names: Option<&[&&str]>,
         shared_context: &SharedContext,
         entry_options: Option<&BenchOptions>,
+        custom_meta: &[(&str, &str)],
         tree_painter: &RefCell<TreePainter>,
         is_last_entry: bool,
     ) {
-        use crate::bench::BenchContext;
-
         let entry_display_name = bench_entry.display_name();
 
-        // User runtime options override all other options.
-        let options: BenchOptions;
-        let options: &BenchOptions = match entry_options {
-            None => &self.bench_options,
-            Some(entry_options) => {
-                options = self.bench_options.overwrite(entry_options);
-                &options
-            }
-        };
+        let options = self.entry_options(bench_entry, entry_options);
+        let options: &BenchOptions = &options;
+
+        let should_ignore = self.should_ignore(options.ignore.unwrap_or_default());
+
+        if action.is_list() && self.format.is_json() {
+            self.list_json_entry(
+                bench_entry,
+                bench_arg_names,
+                options,
+                custom_meta,
+                should_ignore,
+                tree_painter,
+            );
+            return;
+        }
 
-        if self.should_ignore(options.ignore.unwrap_or_default()) {
+        if should_ignore {
             tree_painter.borrow_mut().ignore_leaf(entry_display_name, is_last_entry);
             return;
         }
@@ -265,27 +676,22 @@ impl Divan {
             return;
         }
 
-        let mut thread_counts: Vec<NonZeroUsize> = options
-            .threads
-            .as_deref()
-            .unwrap_or_default()
-            .iter()
-            .map(|&n| match NonZeroUsize::new(n) {
-                Some(n) => n,
-                None => crate::util::known_parallelism(),
-            })
-            .collect();
-
-        thread_counts.sort_unstable();
-        thread_counts.dedup();
+        let thread_counts = &thread_counts(options);
 
-        let thread_counts: &[NonZeroUsize] =
-            if thread_counts.is_empty() { &[NonZeroUsize::MIN] } else { &thread_counts };
+        // Benchmarks over both types and arguments are summarized in grids by
+        // the path of their generic function.
+        let grid_path = match bench_entry {
+            AnyBenchEntry::GenericBench(_) if bench_arg_names.is_some() => {
+                entry_path.strip_suffix(entry_display_name).and_then(|path| path.strip_suffix("::"))
+            }
+            _ => None,
+        };
 
         // Whether we should emit child branches for thread counts.
         let has_thread_branches = thread_counts.len() > 1;
 
         let run_bench = |bench_display_name: &str,
+                         bench_path: &str,
                          is_last_bench: bool,
                          with_bencher: &dyn Fn(Bencher)| {
             if has_thread_branches {
@@ -304,26 +710,93 @@ impl Divan {
                         .start_leaf(&format!("t={thread_count}"), is_last_thread_count);
                 }
 
-                let mut bench_context = BenchContext::new(shared_context, options, thread_count);
-                with_bencher(Bencher::new(&mut bench_context));
+                let leaf_path = if has_thread_branches {
+                    format!("{bench_path}::t={thread_count}")
+                } else {
+                    bench_path.to_owned()
+                };
+
+                let early_result = shared_context
+                    .early_results
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remove(&leaf_path);
+
+                let Measurement { run_stats, run_durations, run_samples, wall_time } =
+                    match early_result {
+                        Some(measurement) => measurement,
+                        None => {
+                            shared_context.progress.start_bench();
+
+                            let measure = || {
+                                measure(
+                                    shared_context,
+                                    options,
+                                    thread_count,
+                                    self.get_measured_runs(shared_context.action),
+                                    self.keeps_durations(),
+                                    self.on_samples.is_some(),
+                                    bench_display_name,
+                                    bench_path,
+                                    with_bencher,
+                                )
+                            };
+
+                            // Test every variant rather than stopping at the
+                            // first panic, which the hook already printed.
+                            if action.is_test() {
+                                let passed = panic::catch_unwind(AssertUnwindSafe(measure)).is_ok();
+                                tree_painter.borrow_mut().finish_test_leaf(&leaf_path, passed);
+                                continue;
+                            }
+
+                            measure()
+                        }
+                    };
+
+                if let Some(on_samples) = &self.on_samples {
+                    run_samples.iter().for_each(on_samples);
+                }
+
+                if let Some(mut stats) = Stats::aggregate(run_stats) {
+                    stats.run_variance = RunVariance::new(&run_durations);
 
-                if !bench_context.did_run {
-                    eprintln!(
-                        "warning: No benchmark function registered for '{bench_display_name}'"
+                    let comparison = self.save_and_compare(
+                        shared_context,
+                        &run_durations.concat(),
+                        &leaf_path,
+                        custom_meta,
                     );
-                }
 
-                let should_compute_stats =
-                    bench_context.did_run && shared_context.action.is_bench();
+                    if comparison.as_ref().is_some_and(Comparison::is_regression) {
+                        crate::failed::record(bench_path);
+                    }
+
+                    shared_context.progress.finish_bench();
+
+                    let mut tree_painter = tree_painter.borrow_mut();
 
-                if should_compute_stats {
-                    let stats = bench_context.compute_stats();
-                    tree_painter.borrow_mut().finish_leaf(
+                    tree_painter.record_wall_time(leaf_path, wall_time, stats.total_time);
+
+                    tree_painter.finish_leaf(
                         is_last_thread_count,
                         &stats,
+                        comparison.as_ref(),
+                        custom_meta,
                         self.bytes_format,
                     );
+
+                    // Grid cells cannot show multiple thread counts.
+                    if let Some(grid_path) = grid_path.filter(|_| !has_thread_branches) {
+                        tree_painter.record_grid_cell(
+                            grid_path,
+                            entry_display_name,
+                            bench_display_name,
+                            &stats,
+                        );
+                    }
                 } else {
+                    shared_context.progress.finish_bench();
                     tree_painter.borrow_mut().finish_empty_leaf();
                 }
             }
@@ -334,7 +807,9 @@ impl Divan {
         };
 
         match bench_entry.bench_runner() {
-            BenchEntryRunner::Plain(bench) => run_bench(entry_display_name, is_last_entry, bench),
+            BenchEntryRunner::Plain(bench) => {
+                run_bench(entry_display_name, entry_path, is_last_entry, bench)
+            }
 
             BenchEntryRunner::Args(bench_runner) => {
                 tree_painter.borrow_mut().start_parent(entry_display_name, is_last_entry);
@@ -347,7 +822,9 @@ impl Divan {
                     let is_last_arg = i == bench_arg_names.len() - 1;
                     let arg_index = util::slice_ptr_index(orig_arg_names, arg_name);
 
-                    run_bench(arg_name, is_last_arg, &|bencher| {
+                    let arg_path = format!("{entry_path}::{arg_name}");
+
+                    run_bench(arg_name, &arg_path, is_last_arg, &|bencher| {
                         bench_runner.bench(bencher, arg_index);
                     });
                 }
@@ -356,114 +833,653 @@ impl Divan {
             }
         }
     }
-}
 
-/// Makes `Divan::skip_regex` input polymorphic.
-pub trait SkipRegex {
-    fn skip_regex(self, divan: &mut Divan);
-}
+    /// Returns the final options of an entry.
+    fn entry_options<'a>(
+        &'a self,
+        bench_entry: AnyBenchEntry,
+        entry_options: Option<&'a BenchOptions>,
+    ) -> Cow<'a, BenchOptions<'a>> {
+        // User runtime options override all other options.
+        let mut options: Cow<BenchOptions> = match entry_options {
+            None => Cow::Borrowed(&self.bench_options),
+            Some(entry_options) => Cow::Owned(self.bench_options.overwrite(entry_options)),
+        };
 
-/// Configuration options.
-impl Divan {
-    /// Creates an instance with options set by parsing CLI arguments.
-    pub fn from_args() -> Self {
-        Self::default().config_with_args()
-    }
+        // The user's callback sees and adjusts the final options.
+        if let Some(configure_entries) = &self.configure_entries {
+            let info = EntryInfo { entry: bench_entry };
+            configure_entries(info, &mut EntryOptions { options: options.to_mut() });
+        }
 
-    /// Sets options by parsing CLI arguments.
-    ///
-    /// This may override any previously-set options.
-    #[must_use]
-    pub fn config_with_args(mut self) -> Self {
-        let mut command = crate::cli::command();
+        options
+    }
 
-        let matches = command.get_matches_mut();
-        let is_exact = matches.get_flag("exact");
+    /// The number of times to measure each benchmark for `action`.
+    fn get_measured_runs(&self, action: Action) -> usize {
+        if action.is_bench() {
+            self.get_runs()
+        } else {
+            1
+        }
+    }
 
-        let mut parse_filter = |filter: &String| {
-            if is_exact {
-                Filter::Exact(filter.to_owned())
-            } else {
-                match Regex::new(filter) {
-                    Ok(r) => Filter::Regex(r),
-                    Err(error) => {
-                        let kind = clap::error::ErrorKind::ValueValidation;
-                        command.error(kind, error).exit();
-                    }
-                }
-            }
-        };
+    /// Returns `true` if sample times are needed after computing statistics.
+    fn keeps_durations(&self) -> bool {
+        self.get_runs() > 1 || self.save_baseline.is_some() || self.baseline.is_some()
+    }
 
-        if let Some(filters) = matches.get_many::<String>("filter") {
-            self.filters.extend(filters.map(&mut parse_filter));
+    /// Measures benchmarks before `run_tree` reaches them, so that it can print
+    /// their results without measuring them again.
+    ///
+    /// Benchmarks with a `priority` are measured first, in order of descending
+    /// priority. With `--jobs`, all single-threaded benchmarks are measured
+    /// concurrently in that order. Multi-threaded benchmarks would compete for
+    /// CPUs with concurrent ones, and the child CPU time of `child_cpu_time`
+    /// benchmarks would include children of concurrent ones, so these are then
+    /// left to `run_tree`.
+    ///
+    /// Results are kept in `early_results` rather than printed as they finish,
+    /// because each row is drawn under its parents with the tree prefix and
+    /// column widths of its position in the tree. Printing rows out of order
+    /// would break the tree and interleave unrelated groups, and terse output
+    /// would no longer match the order of `--list`.
+    fn run_early(&self, tree: &[EntryTree], shared_context: &SharedContext) {
+        let jobs = self.get_jobs();
+
+        let mut queue = Vec::new();
+        self.collect_early_jobs(tree, "", None, jobs > 1, &mut queue);
+
+        if queue.is_empty() {
+            return;
         }
 
-        if let Some(skip_filters) = matches.get_many::<String>("skip") {
-            self.skip_filters.extend(skip_filters.map(&mut parse_filter));
-        }
+        // Stable sorting keeps tree order within the same priority.
+        queue.sort_by_key(|job| Reverse(job.priority));
 
-        self.action = if matches.get_flag("list") {
-            Action::List
-        } else if matches.get_flag("test") || !matches.get_flag("bench") {
-            // Either of:
-            // `cargo bench -- --test`
-            // `cargo test --benches`
-            Action::Test
-        } else {
-            Action::Bench
-        };
+        if jobs > 1 {
+            eprintln!(
+                "warning: Measuring up to {jobs} benchmarks at a time, so results are skewed by contention for caches, memory bandwidth, and CPU frequency"
+            );
 
-        if let Some(&color) = matches.get_one("color") {
-            self.color = color;
+            let cpu_count = util::known_parallelism().get();
+            if jobs > cpu_count {
+                eprintln!(
+                    "warning: {jobs} jobs exceed the {cpu_count} available CPUs, so benchmarks also compete for CPU time"
+                );
+            }
         }
 
-        if matches.get_flag("ignored") {
-            self.run_ignored = RunIgnored::Only;
-        } else if matches.get_flag("include-ignored") {
-            self.run_ignored = RunIgnored::Yes;
-        }
+        let runs = self.get_measured_runs(shared_context.action);
+        let keeps_durations = self.keeps_durations();
+        let keeps_samples = self.on_samples.is_some();
 
-        if let Some(&timer) = matches.get_one("timer") {
-            self.timer = timer;
-        }
+        // Jobs are taken in order, so that a panic stops all later benchmarks
+        // from starting.
+        let next_job = AtomicUsize::new(0);
+        let first_panic = Mutex::new(None);
 
-        if let Some(&sorting_attr) = matches.get_one("sortr") {
-            self.reverse_sort = true;
-            self.sorting_attr = sorting_attr;
-        } else if let Some(&sorting_attr) = matches.get_one("sort") {
-            self.reverse_sort = false;
-            self.sorting_attr = sorting_attr;
-        }
+        let work = |worker: usize| {
+            if jobs > 1 {
+                util::pin_current_thread(worker);
+            }
 
-        if let Some(&sample_count) = matches.get_one("sample-count") {
-            self.bench_options.sample_count = Some(sample_count);
-        }
+            while first_panic.lock().unwrap_or_else(PoisonError::into_inner).is_none() {
+                let Some(job) = queue.get(next_job.fetch_add(1, Relaxed)) else {
+                    break;
+                };
 
-        if let Some(&sample_size) = matches.get_one("sample-size") {
-            self.bench_options.sample_size = Some(sample_size);
-        }
+                let with_bencher = |bencher: Bencher| match job.runner {
+                    BenchEntryRunner::Plain(bench) => bench(bencher),
+                    BenchEntryRunner::Args(bench_runner) => {
+                        bench_runner().bench(bencher, job.arg_index.unwrap_or_default())
+                    }
+                };
+
+                shared_context.progress.start_bench();
+
+                let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                    measure(
+                        shared_context,
+                        &job.options,
+                        job.thread_count,
+                        runs,
+                        keeps_durations,
+                        keeps_samples,
+                        job.display_name,
+                        &job.bench_path,
+                        &with_bencher,
+                    )
+                }));
+
+                shared_context.progress.finish_bench();
+
+                match result {
+                    Ok(measurement) => {
+                        shared_context
+                            .early_results
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .insert(job.leaf_path.clone(), measurement);
+                    }
+                    Err(panic) => {
+                        first_panic
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .get_or_insert(panic);
+                    }
+                }
+            }
+        };
 
-        if let Some(thread_counts) = matches.get_many::<usize>("threads") {
-            let mut threads: Vec<usize> = thread_counts.copied().collect();
-            threads.sort_unstable();
-            threads.dedup();
-            self.bench_options.threads = Some(Cow::Owned(threads));
-        }
+        if jobs > 1 {
+            let work = &work;
 
-        if let Some(&ParsedSeconds(min_time)) = matches.get_one("min-time") {
-            self.bench_options.min_time = Some(min_time);
+            thread::scope(|scope| {
+                for worker in 0..jobs.min(queue.len()) {
+                    scope.spawn(move || work(worker));
+                }
+            });
+        } else {
+            work(0);
         }
 
-        if let Some(&ParsedSeconds(max_time)) = matches.get_one("max-time") {
-            self.bench_options.max_time = Some(max_time);
+        // Fail before printing any results, rather than when `run_tree`
+        // reaches the benchmark.
+        if let Some(panic) = first_panic.into_inner().unwrap_or_else(PoisonError::into_inner) {
+            panic::resume_unwind(panic);
         }
+    }
 
-        if let Some(mut skip_ext_time) = matches.get_many::<bool>("skip-ext-time") {
+    /// Collects benchmarks to measure early in the order that `run_tree` runs
+    /// them, which is every single-threaded benchmark without `child_cpu_time`
+    /// if `concurrent` and otherwise only benchmarks with a `priority`.
+    fn collect_early_jobs<'a>(
+        &self,
+        tree: &'a [EntryTree],
+        parent_path: &str,
+        parent_options: Option<&BenchOptions>,
+        concurrent: bool,
+        jobs: &mut Vec<EarlyJob<'a>>,
+    ) {
+        for child in tree {
+            let name = child.display_name();
+
+            let path = if parent_path.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{parent_path}::{name}")
+            };
+
+            // Overwrite `parent_options` with `child_options` if applicable.
+            let options: BenchOptions;
+            let options: Option<&BenchOptions> = match (parent_options, child.bench_options()) {
+                (None, None) => None,
+                (Some(options), None) | (None, Some(options)) => Some(options),
+                (Some(parent_options), Some(child_options)) => {
+                    options = child_options.overwrite(parent_options);
+                    Some(&options)
+                }
+            };
+
+            let (entry, args) = match child {
+                EntryTree::Leaf { entry, args } => (*entry, args),
+                EntryTree::Parent { children, .. } => {
+                    self.collect_early_jobs(children, &path, options, concurrent, jobs);
+                    continue;
+                }
+            };
+
+            let options = self.entry_options(entry, options);
+            let priority = options.priority.unwrap_or_default();
+
+            if self.should_ignore(options.ignore.unwrap_or_default())
+                || (!concurrent && priority == 0)
+            {
+                continue;
+            }
+
+            // Child CPU time is process-wide, so concurrent benchmarks would
+            // count each other's children.
+            if concurrent && options.child_cpu_time.unwrap_or_default() {
+                continue;
+            }
+
+            let thread_counts = thread_counts(&options);
+            let has_thread_branches = thread_counts.len() > 1;
+
+            let variants: Vec<(Option<usize>, &str, String)> = match entry.bench_runner() {
+                BenchEntryRunner::Plain(_) => vec![(None, entry.display_name(), path)],
+                BenchEntryRunner::Args(bench_runner) => {
+                    let orig_arg_names = bench_runner().arg_names();
+
+                    args.as_deref()
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|&arg_name| {
+                            let arg_index = util::slice_ptr_index(orig_arg_names, arg_name);
+                            (Some(arg_index), *arg_name, format!("{path}::{arg_name}"))
+                        })
+                        .collect()
+                }
+            };
+
+            for (arg_index, display_name, bench_path) in variants {
+                for &thread_count in &thread_counts {
+                    if concurrent && thread_count != NonZeroUsize::MIN {
+                        continue;
+                    }
+
+                    let leaf_path = if has_thread_branches {
+                        format!("{bench_path}::t={thread_count}")
+                    } else {
+                        bench_path.clone()
+                    };
+
+                    jobs.push(EarlyJob {
+                        runner: *entry.bench_runner(),
+                        arg_index,
+                        thread_count,
+                        priority,
+                        options: options.as_ref().clone().into_owned(),
+                        display_name,
+                        bench_path: bench_path.clone(),
+                        leaf_path,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Emits a JSON object for each listed variant of the entry.
+    fn list_json_entry(
+        &self,
+        bench_entry: AnyBenchEntry,
+        bench_arg_names: Option<&[&&str]>,
+        options: &BenchOptions,
+        custom_meta: &[(&str, &str)],
+        ignored: bool,
+        tree_painter: &RefCell<TreePainter>,
+    ) {
+        let mut tree_painter = tree_painter.borrow_mut();
+
+        let entry_display_name = bench_entry.display_name();
+        let location = bench_entry.meta().location;
+        let threads = options.threads.as_deref();
+
+        let mut parameters = Vec::<(&str, &str)>::new();
+
+        if let AnyBenchEntry::GenericBench(entry) = bench_entry {
+            if let Some(ty) = &entry.ty {
+                parameters.push(("type", ty.raw_name()));
+            }
+            if let Some(const_value) = &entry.const_value {
+                parameters.push(("const", const_value.name()));
+            }
+        }
+
+        match bench_arg_names {
+            None => {
+                tree_painter.list_json_leaf(
+                    entry_display_name,
+                    location,
+                    ignored,
+                    &parameters,
+                    custom_meta,
+                    threads,
+                );
+            }
+
+            Some(bench_arg_names) => {
+                tree_painter.start_parent(entry_display_name, false);
+
+                for &&arg_name in bench_arg_names {
+                    parameters.push(("arg", arg_name));
+                    tree_painter.list_json_leaf(
+                        arg_name,
+                        location,
+                        ignored,
+                        &parameters,
+                        custom_meta,
+                        threads,
+                    );
+                    parameters.pop();
+                }
+
+                tree_painter.finish_parent();
+            }
+        }
+    }
+}
+
+/// Measures a benchmark `runs` times.
+fn measure(
+    shared_context: &SharedContext,
+    options: &BenchOptions,
+    thread_count: NonZeroUsize,
+    runs: usize,
+    keeps_durations: bool,
+    keeps_samples: bool,
+    bench_display_name: &str,
+    bench_path: &str,
+    with_bencher: &dyn Fn(Bencher),
+) -> Measurement {
+    let start_time = Instant::now();
+
+    let mut run_stats = Vec::with_capacity(runs);
+    let mut run_durations = Vec::with_capacity(runs);
+    let mut run_samples = Vec::new();
+
+    for _ in 0..runs {
+        let mut bench_context = BenchContext::new(shared_context, options, thread_count);
+
+        // Record failure before continuing to unwind.
+        let run_result = panic::catch_unwind(AssertUnwindSafe(|| {
+            with_bencher(Bencher::new(&mut bench_context));
+        }));
+        if let Err(panic) = run_result {
+            crate::failed::record(bench_path);
+            panic::resume_unwind(panic);
+        }
+
+        if !bench_context.did_run {
+            eprintln!("warning: No benchmark function registered for '{bench_display_name}'");
+            break;
+        }
+
+        if bench_context.exceeded_max_sample_time {
+            eprintln!(
+                "warning: Stopped sampling '{bench_path}' early because a single iteration exceeded the maximum sample time"
+            );
+        }
+
+        if shared_context.action.is_bench() {
+            run_stats.push(bench_context.compute_stats());
+
+            if keeps_durations {
+                run_durations.push(bench_context.iter_durations());
+            }
+
+            if keeps_samples {
+                run_samples.push(bench_context.public_samples(bench_path));
+            }
+        }
+    }
+
+    Measurement { run_stats, run_durations, run_samples, wall_time: start_time.elapsed().into() }
+}
+
+/// Returns the distinct thread counts to run a benchmark with, which is 1 if
+/// unspecified.
+fn thread_counts(options: &BenchOptions) -> Vec<NonZeroUsize> {
+    let mut thread_counts: Vec<NonZeroUsize> = options
+        .threads
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|&n| match NonZeroUsize::new(n) {
+            Some(n) => n,
+            None => crate::util::known_parallelism(),
+        })
+        .collect();
+
+    thread_counts.sort_unstable();
+    thread_counts.dedup();
+
+    if thread_counts.is_empty() {
+        thread_counts.push(NonZeroUsize::MIN);
+    }
+
+    thread_counts
+}
+
+/// Formats cargo features for printing, where `None` means they are unknown.
+fn fmt_features(features: Option<&[String]>) -> String {
+    match features {
+        None => "unknown".to_owned(),
+        Some([]) => "none".to_owned(),
+        Some(features) => features.join(", "),
+    }
+}
+
+/// Formats a CPU timestamp counter frequency in gigahertz for printing.
+fn fmt_frequency(frequency: NonZeroU64) -> String {
+    format!("{:.3} GHz", frequency.get() as f64 / 1e9)
+}
+
+/// Makes `Divan::skip_regex` input polymorphic.
+pub trait SkipRegex {
+    fn skip_regex(self, divan: &mut Divan);
+}
+
+impl SkipRegex for Regex {
+    fn skip_regex(self, divan: &mut Divan) {
+        divan.skip_filters.push(Filter::Regex(self));
+    }
+}
+
+impl SkipRegex for &str {
+    #[track_caller]
+    fn skip_regex(self, divan: &mut Divan) {
+        Regex::new(self).unwrap().skip_regex(divan);
+    }
+}
+
+impl SkipRegex for String {
+    #[track_caller]
+    fn skip_regex(self, divan: &mut Divan) {
+        self.as_str().skip_regex(divan)
+    }
+}
+
+/// Configuration options.
+impl Divan {
+    /// Creates an instance with options set by parsing CLI arguments.
+    pub fn from_args() -> Self {
+        Self::default().config_with_args()
+    }
+
+    /// Sets options by parsing CLI arguments.
+    ///
+    /// This may override any previously-set options.
+    #[must_use]
+    pub fn config_with_args(mut self) -> Self {
+        let mut command = crate::cli::command();
+
+        let matches = command.get_matches_mut();
+        let is_exact = matches.get_flag("exact");
+
+        let mut parse_filter = |filter: &str| {
+            if is_exact {
+                Filter::Exact(filter.to_owned())
+            } else {
+                match Regex::new(filter) {
+                    Ok(r) => Filter::Regex(r),
+                    Err(error) => {
+                        let kind = clap::error::ErrorKind::ValueValidation;
+                        command.error(kind, error).exit();
+                    }
+                }
+            }
+        };
+
+        if let Some(filters) = matches.get_many::<String>("filter") {
+            self.filters.extend(filters.map(|filter| parse_filter(filter)));
+        }
+
+        if let Some(skip_filters) = matches.get_many::<String>("skip") {
+            self.skip_filters.extend(skip_filters.map(|filter| parse_filter(filter)));
+        }
+
+        if let Some(values) = matches.get_many::<String>("arg") {
+            self.arg_filters.extend(values.cloned());
+        }
+
+        if let Some(paths) = matches.get_many::<PathBuf>("filter-file") {
+            for path in paths {
+                let contents = match fs::read_to_string(path) {
+                    Ok(contents) => contents,
+                    Err(error) => {
+                        let kind = clap::error::ErrorKind::Io;
+                        let message = format!("failed to read '{}': {error}", path.display());
+                        command.error(kind, message).exit();
+                    }
+                };
+
+                for line in FilterFileLine::parse_all(&contents) {
+                    match line {
+                        FilterFileLine::Include(pattern) => {
+                            self.filters.push(parse_filter(pattern));
+                        }
+                        FilterFileLine::Exclude(pattern) => {
+                            self.skip_filters.push(parse_filter(pattern));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.action = if matches.get_flag("calibrate") {
+            Action::Calibrate
+        } else if matches.get_flag("list") {
+            Action::List
+        } else if matches.get_flag("test") || !matches.get_flag("bench") {
+            // Either of:
+            // `cargo bench -- --test`
+            // `cargo test --benches`
+            Action::Test
+        } else {
+            Action::Bench
+        };
+
+        if let Some(&color) = matches.get_one("color") {
+            self.color = color;
+        }
+
+        if let Some(&PrivEnum(format)) = matches.get_one::<PrivEnum<OutputFormat>>("format") {
+            if format.is_json() && !self.action.is_list() {
+                let kind = clap::error::ErrorKind::ArgumentConflict;
+                command.error(kind, "'--format json' is only supported with '--list'").exit();
+            }
+
+            self.format = format;
+        }
+
+        if let Some(&PrivEnum(table_style)) = matches.get_one::<PrivEnum<TableStyle>>("table-style")
+        {
+            self.table_style = table_style;
+        }
+
+        if let Some(&PrivEnum(metric)) = matches.get_one::<PrivEnum<GridMetric>>("grid") {
+            self.grid_metric = Some(metric);
+        }
+
+        if let Some(&PrivEnum(format)) = matches.get_one::<PrivEnum<GridFormat>>("grid-format") {
+            self.grid_format = format;
+        }
+
+        if let Some(&PrivEnum(separator)) =
+            matches.get_one::<PrivEnum<ThousandsSeparator>>("thousands-separator")
+        {
+            self.thousands_separator = separator;
+        }
+
+        if matches.get_flag("rerun-failed") {
+            self.rerun_failed = true;
+        }
+
+        if let Some(&shard) = matches.get_one::<Shard>("shard") {
+            self.shard = Some(shard);
+        }
+
+        if let Some(name) = matches.get_one::<String>("save-baseline") {
+            self.save_baseline = Some(name.clone());
+        }
+
+        if let Some(name) = matches.get_one::<String>("baseline") {
+            self.baseline = Some(name.clone());
+        }
+
+        if let Some(&PrivEnum(test)) = matches.get_one("significance-test") {
+            self.significance_test = test;
+        }
+
+        if matches.get_flag("ignored") {
+            self.run_ignored = RunIgnored::Only;
+        } else if matches.get_flag("include-ignored") {
+            self.run_ignored = RunIgnored::Yes;
+        }
+
+        if let Some(&timer) = matches.get_one("timer") {
+            self.timer = timer;
+        }
+
+        if let Some(&sorting_attr) = matches.get_one("sortr") {
+            self.reverse_sort = true;
+            self.sorting_attr = sorting_attr;
+        } else if let Some(&sorting_attr) = matches.get_one("sort") {
+            self.reverse_sort = false;
+            self.sorting_attr = sorting_attr;
+        }
+
+        if let Some(&sample_count) = matches.get_one("sample-count") {
+            self.bench_options.sample_count = Some(sample_count);
+        }
+
+        if let Some(&sample_size) = matches.get_one("sample-size") {
+            self.bench_options.sample_size = Some(sample_size);
+        }
+
+        if let Some(thread_counts) = matches.get_many::<usize>("threads") {
+            let mut threads: Vec<usize> = thread_counts.copied().collect();
+            threads.sort_unstable();
+            threads.dedup();
+            self.bench_options.threads = Some(Cow::Owned(threads));
+        }
+
+        if let Some(&ParsedSeconds(min_time)) = matches.get_one("min-time") {
+            self.bench_options.min_time = Some(min_time);
+        }
+
+        if let Some(&ParsedSeconds(max_time)) = matches.get_one("max-time") {
+            self.bench_options.max_time = Some(max_time);
+        }
+
+        if let Some(&ParsedSeconds(max_sample_time)) = matches.get_one("max-sample-time") {
+            self.bench_options.max_sample_time = Some(max_sample_time);
+        }
+
+        if let Some(&ParsedSeconds(time_budget)) = matches.get_one("time-budget") {
+            self.time_budget = Some(time_budget);
+        }
+
+        if let Some(&ParsedSeconds(warm_up_time)) = matches.get_one("warm-up-time") {
+            self.bench_options.warm_up_time = Some(warm_up_time);
+        }
+
+        if let Some(mut serializing_fences) = matches.get_many::<bool>("serializing-fences") {
+            // If the option is present without a value, then it's `true`.
+            self.serializing_fences = matches!(serializing_fences.next(), Some(true) | None);
+        }
+
+        if let Some(mut skip_ext_time) = matches.get_many::<bool>("skip-ext-time") {
             // If the option is present without a value, then it's `true`.
             self.bench_options.skip_ext_time =
                 Some(matches!(skip_ext_time.next(), Some(true) | None));
         }
 
+        if let Some(mut fastest_iter) = matches.get_many::<bool>("fastest-iter") {
+            // If the option is present without a value, then it's `true`.
+            self.bench_options.fastest_iter =
+                Some(matches!(fastest_iter.next(), Some(true) | None));
+        }
+
+        if let Some(mut child_cpu_time) = matches.get_many::<bool>("child-cpu-time") {
+            // If the option is present without a value, then it's `true`.
+            self.bench_options.child_cpu_time =
+                Some(matches!(child_cpu_time.next(), Some(true) | None));
+        }
+
         if let Some(&count) = matches.get_one::<MaxCountUInt>("items-count") {
             self.counter_mut(ItemsCount::new(count));
         }
@@ -480,6 +1496,26 @@ impl Divan {
             self.counter_mut(CharsCount::new(count));
         }
 
+        if let Some(&runs) = matches.get_one::<u32>("runs") {
+            self.runs = Some(runs as usize);
+        }
+
+        if let Some(&jobs) = matches.get_one::<u32>("jobs") {
+            self.jobs = Some(jobs as usize);
+        }
+
+        if let Some(&sig_figs) = matches.get_one::<u8>("sig-figs") {
+            self.sig_figs = Some(sig_figs.into());
+        }
+
+        if let Some(&depth) = matches.get_one::<u32>("depth") {
+            self.depth = Some(depth as usize);
+        }
+
+        if let Some(&name_width) = matches.get_one::<u32>("name-width") {
+            self.name_width = Some(name_width as usize);
+        }
+
         self
     }
 
@@ -515,6 +1551,124 @@ impl Divan {
         self
     }
 
+    /// Only run benchmarks that failed in the last run.
+    ///
+    /// Benchmarks are recorded as failed if they panic, or if their sample
+    /// times changed significantly and got slower than [`Divan::baseline`].
+    /// Records are kept next to the benchmark executable, and benchmarks that
+    /// did not run, such as because of filters, keep their records. Panics are
+    /// not recorded if the last run used `panic = "abort"`.
+    ///
+    /// This option is equivalent to the `--rerun-failed` CLI argument.
+    #[must_use]
+    pub fn rerun_failed(mut self) -> Self {
+        self.rerun_failed = true;
+        self
+    }
+
+    /// Only run the `index`-th of `count` disjoint subsets of benchmarks, where
+    /// `index` starts at 1.
+    ///
+    /// This splits large suites across parallel CI machines. Shards are chosen
+    /// from benchmarks that pass filters, by sorting their paths and assigning
+    /// them round-robin, so every machine agrees on the assignment. Results of
+    /// different shards can then be merged with [`Aggregate`](crate::Aggregate).
+    ///
+    /// This option is equivalent to the `--shard index/count` CLI argument or
+    /// `DIVAN_SHARD` environment variable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is 0 or greater than `count`.
+    #[track_caller]
+    #[must_use]
+    pub fn shard(mut self, index: usize, count: usize) -> Self {
+        assert!(
+            index != 0 && index <= count,
+            "shard index must be within 1..={count}, got {index}"
+        );
+
+        self.shard = Some(Shard { index, count });
+        self
+    }
+
+    /// Saves per-iteration sample times of benchmarks under `name`, for later
+    /// comparison with [`Divan::baseline`].
+    ///
+    /// Baselines are stored next to the benchmark executable. Saving into an
+    /// existing baseline replaces the samples of benchmarks that run and keeps
+    /// the others, so that filtered or [sharded](Self::shard) runs can add to
+    /// it.
+    ///
+    /// This option is equivalent to the `--save-baseline` CLI argument or
+    /// `DIVAN_SAVE_BASELINE` environment variable.
+    #[inline]
+    pub fn save_baseline(mut self, name: impl Into<String>) -> Self {
+        self.save_baseline = Some(name.into());
+        self
+    }
+
+    /// Compares benchmarks against sample times saved under `name` by
+    /// [`Divan::save_baseline`].
+    ///
+    /// Below each benchmark, this emits the relative change of each time
+    /// statistic and the result of a two-sample
+    /// [Kolmogorov–Smirnov test](https://en.wikipedia.org/wiki/Kolmogorov%E2%80%93Smirnov_test)
+    /// over the full distributions of sample times. Unlike comparing medians,
+    /// this detects changes in the shape of a distribution, such as a new slow
+    /// path taken 5% of the time.
+    ///
+    /// The non-parametric
+    /// [Mann–Whitney U test](https://en.wikipedia.org/wiki/Mann%E2%80%93Whitney_U_test)
+    /// can be used instead with [`Divan::significance_test`], which is more
+    /// sensitive to shifts in the median.
+    ///
+    /// This option is equivalent to the `--baseline` CLI argument or
+    /// `DIVAN_BASELINE` environment variable.
+    #[inline]
+    pub fn baseline(mut self, name: impl Into<String>) -> Self {
+        self.baseline = Some(name.into());
+        self
+    }
+
+    /// Sets the test used by [`Divan::baseline`] to determine whether a
+    /// benchmark changed significantly.
+    ///
+    /// This option is equivalent to the `--significance-test` CLI argument or
+    /// `DIVAN_SIGNIFICANCE_TEST` environment variable.
+    #[inline]
+    pub fn significance_test(mut self, test: SignificanceTest) -> Self {
+        self.significance_test = test;
+        self
+    }
+
+    /// Records the cargo features enabled for the benchmarked crate, which is
+    /// usually done with [`divan::features!`](crate::features).
+    ///
+    /// Features are printed before benchmarking and saved with
+    /// [`Divan::save_baseline`]. When comparing with [`Divan::baseline`], the
+    /// baseline's features are printed so that comparisons across feature
+    /// sets, such as with and without SIMD, are labeled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn main() {
+    ///     divan::Divan::from_args()
+    ///         .features(divan::features!("simd", "std"))
+    ///         .main();
+    /// }
+    /// ```
+    #[must_use]
+    pub fn features<I>(mut self, features: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.features = Some(features.into_iter().map(Into::into).collect());
+        self
+    }
+
     /// Skips benchmarks that match `filter` as a regular expression pattern.
     ///
     /// This option is equivalent to the `--skip filter` CLI argument, without
@@ -552,26 +1706,6 @@ impl Divan {
     /// Panics if `filter` is a string and [`Regex::new`] fails.
     #[must_use]
     pub fn skip_regex(mut self, filter: impl SkipRegex) -> Self {
-        impl SkipRegex for Regex {
-            fn skip_regex(self, divan: &mut Divan) {
-                divan.skip_filters.push(Filter::Regex(self));
-            }
-        }
-
-        impl SkipRegex for &str {
-            #[track_caller]
-            fn skip_regex(self, divan: &mut Divan) {
-                Regex::new(self).unwrap().skip_regex(divan);
-            }
-        }
-
-        impl SkipRegex for String {
-            #[track_caller]
-            fn skip_regex(self, divan: &mut Divan) {
-                self.as_str().skip_regex(divan)
-            }
-        }
-
         filter.skip_regex(&mut self);
         self
     }
@@ -604,6 +1738,72 @@ impl Divan {
         self
     }
 
+    /// Adds filters from the contents of a filter file, which has one regex
+    /// pattern per line.
+    ///
+    /// Benchmarks are only run if they match any pattern. Patterns prefixed
+    /// with `!` instead skip matching benchmarks, like
+    /// [`skip_regex`](Self::skip_regex). Blank lines and lines starting with
+    /// `#` are ignored.
+    ///
+    /// This option is equivalent to the `--filter-file` CLI argument, but
+    /// reads the file contents from a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use divan::Divan;
+    /// let divan = Divan::default().filter_file_contents(
+    ///     "# Hashing without the slow cases.
+    ///     hash::
+    ///     !hash::slow",
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`Regex::new`] fails for any pattern.
+    #[must_use]
+    #[track_caller]
+    pub fn filter_file_contents(mut self, contents: &str) -> Self {
+        for line in FilterFileLine::parse_all(contents) {
+            match line {
+                FilterFileLine::Include(pattern) => {
+                    self.filters.push(Filter::Regex(Regex::new(pattern).unwrap()));
+                }
+                FilterFileLine::Exclude(pattern) => {
+                    self.skip_filters.push(Filter::Regex(Regex::new(pattern).unwrap()));
+                }
+            }
+        }
+        self
+    }
+
+    /// Only runs benchmarks with an [`args`](macro@crate::bench#args) value,
+    /// [`consts`](macro@crate::bench#consts) value, or
+    /// [`types`](macro@crate::bench#types) type named exactly `name`, as
+    /// shown in benchmark outputs.
+    ///
+    /// This makes it quick to remeasure a single point of a sweep, such as
+    /// when combined with a filter for the benchmark's name. Benchmarks
+    /// without any of these parameters are skipped.
+    ///
+    /// This option is equivalent to the `--arg` CLI argument.
+    ///
+    /// # Examples
+    ///
+    /// Calling this repeatedly will run benchmarks with any of the names:
+    ///
+    /// ```
+    /// # use divan::Divan;
+    /// let divan = Divan::default().filter_arg("1024").filter_arg("4096");
+    /// ```
+    #[must_use]
+    pub fn filter_arg(mut self, name: impl Into<String>) -> Self {
+        self.arg_filters.push(name.into());
+        self
+    }
+
     /// Sets the number of sampling iterations.
     ///
     /// This option is equivalent to the `--sample-count` CLI argument.
@@ -627,6 +1827,43 @@ impl Divan {
         self
     }
 
+    /// Repeats the whole measurement of each benchmark `runs` times and
+    /// aggregates the results.
+    ///
+    /// Each run starts afresh, including tuning the sample size, so that a
+    /// temporary slowdown of the machine affects only some runs. The fastest
+    /// and slowest times are the extremes over all runs, and the median time is
+    /// the median of each run's median. Values are clamped to at least 1.
+    ///
+    /// This option is equivalent to the `--runs` CLI argument or `DIVAN_RUNS`
+    /// environment variable.
+    #[inline]
+    pub fn runs(mut self, runs: usize) -> Self {
+        self.runs = Some(runs.max(1));
+        self
+    }
+
+    /// Measures up to `jobs` benchmarks at a time, each on a thread pinned to a
+    /// different CPU. The default is 1.
+    ///
+    /// This shortens the wall time of large suites, such as for smoke-level
+    /// performance testing in CI, at the cost of accuracy: concurrent
+    /// benchmarks contend for caches, memory bandwidth, and CPU frequency, so
+    /// results should only be compared against runs with the same number of
+    /// jobs. Benchmarks start in order of descending
+    /// [`priority`](macro@crate::bench#priority). Multi-threaded benchmarks
+    /// and benchmarks with [`child_cpu_time`](macro@crate::bench#child_cpu_time)
+    /// are still measured on their own, and CPU pinning is only supported on
+    /// Linux. Values are clamped to at least 1.
+    ///
+    /// This option is equivalent to the `--jobs` CLI argument or `DIVAN_JOBS`
+    /// environment variable.
+    #[inline]
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = Some(jobs.max(1));
+        self
+    }
+
     /// Run across multiple threads.
     ///
     /// This enables you to measure contention on [atomics and
@@ -667,6 +1904,51 @@ impl Divan {
         self
     }
 
+    /// Sets the time ceiling for a single sample.
+    ///
+    /// If a sample takes longer, subsequent samples run fewer iterations. If a
+    /// single iteration takes longer, sampling stops early with a warning.
+    ///
+    /// This option is equivalent to the `--max-sample-time` CLI argument or
+    /// `DIVAN_MAX_SAMPLE_TIME` environment variable.
+    #[inline]
+    pub fn max_sample_time(mut self, time: Duration) -> Self {
+        self.bench_options.max_sample_time = Some(time);
+        self
+    }
+
+    /// Shares a time budget for the whole run between benchmarks, to get the
+    /// most precise results within a fixed time slice, such as in CI.
+    ///
+    /// Each benchmark may take up to twice its fair share of the remaining
+    /// budget, which accounts for benchmarks measured at the same time with
+    /// [`Divan::jobs`]. Benchmarks without a fixed
+    /// [`sample_count`](macro@crate::bench#sample_count) stop after 10 samples
+    /// once the standard error of their mean time is within 1%, and otherwise
+    /// continue past the default sample count until their share is spent. Time
+    /// saved by stable benchmarks thus goes to noisier ones. The budget does
+    /// not override [`max_time`](macro@crate::bench#max_time), so benchmarks
+    /// may end sooner.
+    ///
+    /// This option is equivalent to the `--time-budget` CLI argument or
+    /// `DIVAN_TIME_BUDGET` environment variable.
+    #[inline]
+    pub fn time_budget(mut self, time: Duration) -> Self {
+        self.time_budget = Some(time);
+        self
+    }
+
+    /// Spin the CPU for this long before sampling each benchmark, so that
+    /// early samples are not taken at a reduced clock speed.
+    ///
+    /// This option is equivalent to the `--warm-up-time` CLI argument or
+    /// `DIVAN_WARM_UP_TIME` environment variable.
+    #[inline]
+    pub fn warm_up_time(mut self, time: Duration) -> Self {
+        self.bench_options.warm_up_time = Some(time);
+        self
+    }
+
     /// When accounting for `min_time` or `max_time`, skip time external to
     /// benchmarked functions.
     ///
@@ -676,6 +1958,126 @@ impl Divan {
         self.bench_options.skip_ext_time = Some(skip);
         self
     }
+
+    /// Also time single iterations of single-threaded benchmarks to report
+    /// the fastest one, alongside the fastest sample average.
+    ///
+    /// This option is equivalent to the `--fastest-iter` CLI argument or
+    /// `DIVAN_FASTEST_ITER` environment variable.
+    #[inline]
+    pub fn fastest_iter(mut self, enabled: bool) -> Self {
+        self.bench_options.fastest_iter = Some(enabled);
+        self
+    }
+
+    /// Also report the user and system CPU time of child processes per
+    /// iteration, for benchmarks that spawn subprocesses, such as with
+    /// [`Bencher::bench_command`](crate::Bencher::bench_command).
+    ///
+    /// Only children that have exited and been waited on are counted.
+    ///
+    /// This option is equivalent to the `--child-cpu-time` CLI argument or
+    /// `DIVAN_CHILD_CPU_TIME` environment variable.
+    #[inline]
+    pub fn child_cpu_time(mut self, enabled: bool) -> Self {
+        self.bench_options.child_cpu_time = Some(enabled);
+        self
+    }
+
+    /// Emit serializing instructions around sample timestamps, rather than
+    /// only memory fences.
+    ///
+    /// This prevents out-of-order execution from moving work across the start
+    /// or end of a sample, which matters for extremely short benchmarks. It
+    /// uses `cpuid` on x86 and `isb` on AArch64, at the cost of higher
+    /// per-sample overhead.
+    ///
+    /// This option is equivalent to the `--serializing-fences` CLI argument or
+    /// `DIVAN_SERIALIZING_FENCES` environment variable.
+    #[inline]
+    pub fn serializing_fences(mut self, enabled: bool) -> Self {
+        self.serializing_fences = enabled;
+        self
+    }
+
+    /// Times samples with the virtual [`MockClock`](crate::MockClock) rather
+    /// than real time, so that results are deterministic.
+    ///
+    /// This is meant for testing reporters, time budgets, and statistics of code
+    /// built on Divan. Sample sizes are not tuned when using the mock clock, so
+    /// each sample has 1 iteration unless
+    /// [`sample_size`](macro@crate::bench#sample_size) is set.
+    ///
+    /// This requires the `mock_clock` crate feature.
+    #[must_use]
+    #[cfg(feature = "mock_clock")]
+    pub fn mock_clock(mut self) -> Self {
+        self.timer = TimerKind::Mock;
+        self
+    }
+
+    /// Adjusts the options of each benchmark entry at runtime.
+    ///
+    /// The callback receives the entry's metadata and its final options, after
+    /// attribute options, builder methods, and CLI arguments have been
+    /// applied. This makes it possible to configure many benchmarks without
+    /// touching every attribute.
+    ///
+    /// Calling this again replaces the previous callback.
+    ///
+    /// # Examples
+    ///
+    /// Sample benchmarks in an `io` module for longer:
+    ///
+    /// ```
+    /// # use divan::Divan;
+    /// use std::time::Duration;
+    ///
+    /// let divan = Divan::default().configure_entries(|entry, options| {
+    ///     if entry.module_path().split("::").any(|module| module == "io") {
+    ///         options.min_time(Duration::from_secs(5));
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn configure_entries<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(EntryInfo, &mut EntryOptions) + Send + Sync + 'static,
+    {
+        self.configure_entries = Some(Box::new(configure));
+        self
+    }
+
+    /// Calls `on_samples` with the samples of each benchmark run, after they
+    /// are measured and before their statistics are printed.
+    ///
+    /// This makes it possible to apply custom estimators to samples without
+    /// running benchmarks again. It is called on the main thread, even with
+    /// [`Divan::jobs`].
+    ///
+    /// Calling this again replaces the previous callback.
+    ///
+    /// # Examples
+    ///
+    /// Print the fastest sample of each benchmark:
+    ///
+    /// ```
+    /// # use divan::Divan;
+    /// let divan = Divan::default().on_samples(|samples| {
+    ///     if let Some(fastest) = samples.durations().iter().min() {
+    ///         let fastest = *fastest / samples.sample_size();
+    ///         eprintln!("{}: {fastest:?}", samples.path());
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn on_samples<F>(mut self, on_samples: F) -> Self
+    where
+        F: Fn(&Samples) + Send + Sync + 'static,
+    {
+        self.on_samples = Some(Box::new(on_samples));
+        self
+    }
 }
 
 /// Use [`Counter`s](crate::counter::Counter) to get throughput across all
@@ -722,6 +2124,118 @@ impl Divan {
         self
     }
 
+    /// Sets the number of significant figures for durations and throughput in
+    /// benchmark outputs. The default is 4.
+    ///
+    /// Fewer digits reduce noise, whereas more digits make small regressions
+    /// visible. Values are clamped to at least 1.
+    ///
+    /// This option is equivalent to the `--sig-figs` CLI argument or
+    /// `DIVAN_SIG_FIGS` environment variable.
+    #[inline]
+    pub fn sig_figs(mut self, sig_figs: usize) -> Self {
+        self.sig_figs = Some(sig_figs.max(1));
+        self
+    }
+
+    /// Sets the number of tree levels shown in benchmark outputs.
+    ///
+    /// Parents deeper than `depth` are collapsed into the names of their
+    /// children, such as `module::bench::t=4`, which removes their rows and
+    /// indentation from the output of deeply nested suites. By default, all
+    /// levels are shown. Values are clamped to at least 1.
+    ///
+    /// Collapsed names are usually wider than the indentation they replace, so
+    /// combine this with [`name_width`](Self::name_width) to also narrow the
+    /// name column.
+    ///
+    /// This option is equivalent to the `--depth` CLI argument or
+    /// `DIVAN_DEPTH` environment variable.
+    #[inline]
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth.max(1));
+        self
+    }
+
+    /// Sets the width of the name column in benchmark outputs, including tree
+    /// branches.
+    ///
+    /// Names that do not fit have their middle replaced with `…`, or `...` with
+    /// [`TableStyle::Ascii`], so that a
+    /// single long name, such as of a generic type, does not widen the table
+    /// for all benchmarks. By default, the column fits the longest name.
+    /// Values are clamped to at least 1.
+    ///
+    /// Setting this also keeps all columns shown rather than hiding some to fit
+    /// the terminal width.
+    ///
+    /// This option is equivalent to the `--name-width` CLI argument or
+    /// `DIVAN_NAME_WIDTH` environment variable.
+    #[inline]
+    pub fn name_width(mut self, width: usize) -> Self {
+        self.name_width = Some(width.max(1));
+        self
+    }
+
+    /// Sets the separator between groups of thousands in counts, such as of
+    /// iterations, in benchmark outputs.
+    ///
+    /// This option is equivalent to the `--thousands-separator` CLI argument or
+    /// `DIVAN_THOUSANDS_SEPARATOR` environment variable.
+    #[inline]
+    pub fn thousands_separator(mut self, separator: ThousandsSeparator) -> Self {
+        self.thousands_separator = separator;
+        self
+    }
+
+    /// Sets how benchmark results are printed.
+    ///
+    /// [`OutputFormat::Json`] is only supported when listing benchmarks, and
+    /// otherwise prints results as [`OutputFormat::Terse`] does.
+    ///
+    /// This option is equivalent to the `--format` CLI argument or
+    /// `DIVAN_FORMAT` environment variable.
+    #[inline]
+    pub fn format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the characters used to draw the tree and table of benchmark
+    /// outputs.
+    ///
+    /// This option is equivalent to the `--table-style` CLI argument or
+    /// `DIVAN_TABLE_STYLE` environment variable.
+    #[inline]
+    pub fn table_style(mut self, style: TableStyle) -> Self {
+        self.table_style = style;
+        self
+    }
+
+    /// Summarizes benchmarks over both types and arguments in grids of
+    /// `metric`, printed after the benchmark tree.
+    ///
+    /// Grids are only printed with the default pretty output format.
+    /// Benchmarks run with multiple thread counts are excluded.
+    ///
+    /// This option is equivalent to the `--grid` CLI argument or `DIVAN_GRID`
+    /// environment variable.
+    #[inline]
+    pub fn grid(mut self, metric: GridMetric) -> Self {
+        self.grid_metric = Some(metric);
+        self
+    }
+
+    /// Determines how [`grid`](Self::grid) summaries are printed.
+    ///
+    /// This option is equivalent to the `--grid-format` CLI argument or
+    /// `DIVAN_GRID_FORMAT` environment variable.
+    #[inline]
+    pub fn grid_format(mut self, format: GridFormat) -> Self {
+        self.grid_format = format;
+        self
+    }
+
     /// Sets the number of bytes processed.
     ///
     /// This option is equivalent to the `--chars-count` CLI argument or
@@ -731,3 +2245,71 @@ impl Divan {
         self.counter(count.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+    use crate::entry::{BenchEntry, EntryLocation, EntryMeta};
+
+    const fn bench_entry(
+        name: &'static str,
+        get_bench_options: fn() -> BenchOptions<'static>,
+    ) -> BenchEntry {
+        BenchEntry {
+            meta: EntryMeta {
+                display_name: name,
+                raw_name: name,
+                module_path: "suite",
+                location: EntryLocation { file: "lib.rs", line: 1, col: 1 },
+                custom_meta: &[],
+                get_bench_options: Some(get_bench_options),
+                cached_bench_options: OnceLock::new(),
+            },
+            bench: BenchEntryRunner::Plain(|_| {}),
+        }
+    }
+
+    static PLAIN: BenchEntry = bench_entry("plain", BenchOptions::default);
+
+    static CHILD_CPU_TIME: BenchEntry = bench_entry("child_cpu_time", || BenchOptions {
+        child_cpu_time: Some(true),
+        ..Default::default()
+    });
+
+    static PRIORITY: BenchEntry =
+        bench_entry("priority", || BenchOptions { priority: Some(1), ..Default::default() });
+
+    fn early_jobs(concurrent: bool) -> Vec<String> {
+        let tree =
+            EntryTree::from_benches([&PLAIN, &CHILD_CPU_TIME, &PRIORITY].map(AnyBenchEntry::Bench));
+
+        let mut jobs = Vec::new();
+        Divan::default().collect_early_jobs(&tree, "", None, concurrent, &mut jobs);
+        jobs.into_iter().map(|job| job.leaf_path).collect()
+    }
+
+    #[test]
+    fn filter_file_contents() {
+        let divan =
+            Divan::default().filter_file_contents("# Comment\n\nhash::\n ! hash::slow\nsort$\n");
+
+        assert!(divan.filter("hash::fast"));
+        assert!(divan.filter("sort"));
+        assert!(!divan.filter("hash::slow"));
+        assert!(!divan.filter("sort::slow"));
+        assert!(!divan.filter("Comment"));
+    }
+
+    #[test]
+    fn early_jobs_sequential() {
+        assert_eq!(early_jobs(false), ["suite::priority"]);
+    }
+
+    /// Child CPU time is process-wide, so it cannot be measured concurrently.
+    #[test]
+    fn early_jobs_concurrent() {
+        assert_eq!(early_jobs(true), ["suite::plain", "suite::priority"]);
+    }
+}
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use crate::{bench::BenchOptions, entry::AnyBenchEntry};
+
+/// A benchmark entry passed to
+/// [`Divan::configure_entries`](crate::Divan::configure_entries).
+#[derive(Clone, Copy)]
+pub struct EntryInfo<'a> {
+    pub(crate) entry: AnyBenchEntry<'a>,
+}
+
+impl<'a> EntryInfo<'a> {
+    /// Returns the name of the benchmark function.
+    #[inline]
+    pub fn name(&self) -> &'a str {
+        self.entry.meta().display_name
+    }
+
+    /// Returns the `module_path!()` of the benchmark function.
+    #[inline]
+    pub fn module_path(&self) -> &'a str {
+        self.entry.meta().module_path
+    }
+
+    /// Returns the file in which the benchmark function is defined.
+    #[inline]
+    pub fn file(&self) -> &'a str {
+        self.entry.meta().location.file
+    }
+
+    /// Returns the line at which the benchmark function is defined.
+    #[inline]
+    pub fn line(&self) -> u32 {
+        self.entry.meta().location.line
+    }
+
+    /// Returns the value of `key` in the
+    /// [`meta(...)`](macro@crate::bench#meta) attribute option.
+    #[inline]
+    pub fn meta(&self, key: &str) -> Option<&'a str> {
+        let custom_meta = self.entry.meta().custom_meta;
+        custom_meta.iter().find(|(k, _)| *k == key).map(|&(_, value)| value)
+    }
+}
+
+/// The options of a benchmark entry passed to
+/// [`Divan::configure_entries`](crate::Divan::configure_entries).
+///
+/// Each method overrides the [attribute option](macro@crate::bench#options) of
+/// the same name.
+pub struct EntryOptions<'a, 'b> {
+    pub(crate) options: &'a mut BenchOptions<'b>,
+}
+
+impl EntryOptions<'_, '_> {
+    /// Sets the [`sample_count`](macro@crate::bench#sample_count).
+    #[inline]
+    pub fn sample_count(&mut self, count: u32) -> &mut Self {
+        self.options.sample_count = Some(count);
+        self
+    }
+
+    /// Sets the [`sample_size`](macro@crate::bench#sample_size).
+    #[inline]
+    pub fn sample_size(&mut self, count: u32) -> &mut Self {
+        self.options.sample_size = Some(count);
+        self
+    }
+
+    /// Sets the [`min_time`](macro@crate::bench#min_time).
+    #[inline]
+    pub fn min_time(&mut self, time: Duration) -> &mut Self {
+        self.options.min_time = Some(time);
+        self
+    }
+
+    /// Sets the [`max_time`](macro@crate::bench#max_time).
+    #[inline]
+    pub fn max_time(&mut self, time: Duration) -> &mut Self {
+        self.options.max_time = Some(time);
+        self
+    }
+
+    /// Sets the [`max_sample_time`](macro@crate::bench#max_sample_time).
+    #[inline]
+    pub fn max_sample_time(&mut self, time: Duration) -> &mut Self {
+        self.options.max_sample_time = Some(time);
+        self
+    }
+
+    /// Sets the [`warm_up_time`](macro@crate::bench#warm_up_time).
+    #[inline]
+    pub fn warm_up_time(&mut self, time: Duration) -> &mut Self {
+        self.options.warm_up_time = Some(time);
+        self
+    }
+
+    /// Sets [`skip_ext_time`](macro@crate::bench#skip_ext_time).
+    #[inline]
+    pub fn skip_ext_time(&mut self, skip: bool) -> &mut Self {
+        self.options.skip_ext_time = Some(skip);
+        self
+    }
+
+    /// Sets the [`priority`](macro@crate::bench#priority).
+    #[inline]
+    pub fn priority(&mut self, priority: u32) -> &mut Self {
+        self.options.priority = Some(priority);
+        self
+    }
+
+    /// Sets whether the benchmark is [ignored](macro@crate::bench#ignore).
+    #[inline]
+    pub fn ignore(&mut self, ignore: bool) -> &mut Self {
+        self.options.ignore = Some(ignore);
+        self
+    }
+}
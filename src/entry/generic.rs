@@ -2,6 +2,7 @@ use std::{
     any::{Any, TypeId},
     cmp::Ordering,
     mem::ManuallyDrop,
+    ptr,
     sync::OnceLock,
 };
 
@@ -150,7 +151,7 @@ impl EntryConst {
     /// Returns [`PartialOrd::partial_cmp`] ordering if `<` or `>, falling back
     /// to comparing [`ToString::to_string`] otherwise.
     pub(crate) fn cmp_name(&self, other: &Self) -> Ordering {
-        if self.partial_cmp == other.partial_cmp {
+        if ptr::eq(self.partial_cmp as *const (), other.partial_cmp as *const ()) {
             // SAFETY: Both constants have the same comparison function, so they
             // must be the same type.
             if let Some(ordering) = unsafe { (self.partial_cmp)(self.value, other.value) } {
@@ -18,6 +18,9 @@ pub struct EntryMeta {
     /// Where the entry was defined.
     pub location: EntryLocation,
 
+    /// Custom key/value pairs from the `meta(...)` attribute option.
+    pub custom_meta: &'static [(&'static str, &'static str)],
+
     /// Configures the benchmarker via attribute options.
     pub get_bench_options: Option<fn() -> BenchOptions<'static>>,
 
@@ -36,7 +39,7 @@ pub struct EntryLocation {
 
 impl EntryMeta {
     #[inline]
-    pub(crate) fn bench_options(&self) -> Option<&BenchOptions> {
+    pub(crate) fn bench_options(&self) -> Option<&BenchOptions<'_>> {
         Some(self.cached_bench_options.get_or_init(self.get_bench_options?))
     }
 
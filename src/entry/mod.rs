@@ -2,12 +2,14 @@ use std::ptr::NonNull;
 
 use crate::{bench::BenchArgsRunner, Bencher};
 
+mod configure;
 mod generic;
 mod list;
 mod meta;
 mod tree;
 
 pub use self::{
+    configure::{EntryInfo, EntryOptions},
     generic::{EntryConst, EntryType, GenericBenchEntry},
     list::EntryList,
     meta::{EntryLocation, EntryMeta},
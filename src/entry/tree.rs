@@ -50,18 +50,78 @@ impl<'a> EntryTree<'a> {
         result
     }
 
-    /// Returns the maximum span for a name in `tree`.
-    pub fn max_name_span(tree: &[Self], depth: usize) -> usize {
+    /// Returns the maximum span for a name in `tree`, where parents at
+    /// `collapse_depth` or deeper are collapsed into their children's names.
+    ///
+    /// `runtime_threads` are thread counts that override those of entries.
+    pub fn max_name_span(
+        tree: &[Self],
+        collapse_depth: usize,
+        runtime_threads: Option<&[usize]>,
+    ) -> usize {
+        Self::max_name_span_at(tree, 0, 0, collapse_depth, runtime_threads, None)
+    }
+
+    /// Returns the maximum span for a name in `tree` at `depth`, where names
+    /// are prefixed by `collapsed_len` characters of collapsed parents, and
+    /// `parent_threads` are thread counts inherited from groups.
+    fn max_name_span_at(
+        tree: &[Self],
+        depth: usize,
+        collapsed_len: usize,
+        collapse_depth: usize,
+        runtime_threads: Option<&[usize]>,
+        parent_threads: Option<&[usize]>,
+    ) -> usize {
+        // Returns the depth and collapsed prefix length of the children of a
+        // parent. Collapsed parents prefix children with `name::` rather than
+        // nesting them.
+        let nest = |depth: usize, collapsed_len: usize, name_len: usize| {
+            if depth >= collapse_depth {
+                (depth, collapsed_len + name_len + 2)
+            } else {
+                (depth + 1, collapsed_len)
+            }
+        };
+
         tree.iter()
             .map(|node| {
+                let name_len = node.display_name().chars().count();
+
+                let threads = node
+                    .bench_options()
+                    .and_then(|options| options.threads.as_deref())
+                    .or(parent_threads);
+                let leaf_threads = runtime_threads.or(threads);
+
                 let node_name_span = {
                     let prefix_len = depth * 3;
-                    let name_len = node.display_name().chars().count();
-                    prefix_len + name_len
+                    prefix_len + collapsed_len + name_len
                 };
 
+                let (child_depth, child_collapsed_len) = nest(depth, collapsed_len, name_len);
+
                 // The maximum span of any descendent.
-                let children_max_span = Self::max_name_span(node.children(), depth + 1);
+                let children_max_span = Self::max_name_span_at(
+                    node.children(),
+                    child_depth,
+                    child_collapsed_len,
+                    collapse_depth,
+                    runtime_threads,
+                    threads,
+                );
+
+                // The span of thread count leaves, e.g. `t=4`, under a parent
+                // at the given depth.
+                let thread_counts_span = |depth: usize, collapsed_len: usize, name_len: usize| {
+                    if !matches!(node, Self::Leaf { .. }) {
+                        return None;
+                    }
+
+                    let max_len = thread_counts_max_len(leaf_threads?)?;
+                    let (depth, collapsed_len) = nest(depth, collapsed_len, name_len);
+                    Some(depth * 3 + collapsed_len + max_len)
+                };
 
                 // The maximum span of any runtime argument.
                 let args_max_span = node
@@ -69,19 +129,62 @@ impl<'a> EntryTree<'a> {
                     .unwrap_or_default()
                     .iter()
                     .map(|arg| {
-                        let prefix_len = (depth + 1) * 3;
+                        let prefix_len = child_depth * 3;
                         let name_len = arg.chars().count();
-                        prefix_len + name_len
+                        let arg_span = prefix_len + child_collapsed_len + name_len;
+
+                        thread_counts_span(child_depth, child_collapsed_len, name_len)
+                            .map_or(arg_span, |span| span.max(arg_span))
                     })
-                    .max()
+                    .max();
+
+                let leaf_max_span = args_max_span
+                    .or_else(|| thread_counts_span(depth, collapsed_len, name_len))
                     .unwrap_or_default();
 
-                node_name_span.max(children_max_span).max(args_max_span)
+                node_name_span.max(children_max_span).max(leaf_max_span)
             })
             .max()
             .unwrap_or_default()
     }
 
+    /// Returns the expected number of benchmarks run by `tree`, counting each
+    /// argument and thread count separately.
+    ///
+    /// `runtime_threads` are thread counts that override those of entries.
+    pub fn bench_count(tree: &[Self], runtime_threads: Option<&[usize]>) -> usize {
+        fn count(
+            tree: &[EntryTree],
+            runtime_threads: Option<&[usize]>,
+            parent_threads: Option<&[usize]>,
+        ) -> usize {
+            tree.iter()
+                .map(|node| {
+                    let threads = node
+                        .bench_options()
+                        .and_then(|options| options.threads.as_deref())
+                        .or(parent_threads);
+
+                    match node {
+                        EntryTree::Parent { children, .. } => {
+                            count(children, runtime_threads, threads)
+                        }
+                        EntryTree::Leaf { args, .. } => {
+                            let arg_count = args.as_ref().map_or(1, Vec::len);
+                            let thread_count = runtime_threads
+                                .or(threads)
+                                .map_or(1, |threads| thread_counts(threads).len().max(1));
+
+                            arg_count * thread_count
+                        }
+                    }
+                })
+                .sum()
+        }
+
+        count(tree, runtime_threads, None)
+    }
+
     /// Returns the likely span for a given column.
     pub fn common_column_width(tree: &[Self], column: TreeColumn) -> usize {
         // Time and throughput info.
@@ -187,6 +290,43 @@ impl<'a> EntryTree<'a> {
         retain(tree, "", &mut filter);
     }
 
+    /// Removes benchmark variants without an argument, const, or type whose
+    /// name matches the filter.
+    pub fn retain_params(tree: &mut Vec<Self>, filter: &impl Fn(&str) -> bool) {
+        tree.retain_mut(|subtree| match subtree {
+            EntryTree::Parent { children, .. } => {
+                Self::retain_params(children, filter);
+
+                // If no children exist, filter out this parent.
+                !children.is_empty()
+            }
+
+            EntryTree::Leaf { entry, args } => {
+                let generic_match = match entry {
+                    AnyBenchEntry::Bench(_) => false,
+                    AnyBenchEntry::GenericBench(entry) => {
+                        entry
+                            .ty
+                            .as_ref()
+                            .is_some_and(|ty| filter(ty.display_name()) || filter(ty.raw_name()))
+                            || entry.const_value.as_ref().is_some_and(|value| filter(value.name()))
+                    }
+                };
+
+                match args {
+                    _ if generic_match => true,
+                    None => false,
+                    Some(args) => {
+                        args.retain(|arg| filter(arg));
+
+                        // If no arguments exist, filter out this leaf.
+                        !args.is_empty()
+                    }
+                }
+            }
+        });
+    }
+
     /// Sorts the tree by the given ordering.
     pub fn sort_by_attr(tree: &mut [Self], attr: SortingAttr, reverse: bool) {
         let apply_reverse =
@@ -327,7 +467,7 @@ impl<'a> EntryTree<'a> {
         }
     }
 
-    pub fn bench_options(&self) -> Option<&'a BenchOptions> {
+    pub fn bench_options(&self) -> Option<&'a BenchOptions<'a>> {
         self.meta()?.bench_options()
     }
 
@@ -402,3 +542,143 @@ impl<'a> EntryTree<'a> {
         }
     }
 }
+
+/// Returns the maximum length of thread count names, e.g. `t=4`, if there are
+/// multiple thread counts.
+fn thread_counts_max_len(threads: &[usize]) -> Option<usize> {
+    let thread_counts = thread_counts(threads);
+
+    if thread_counts.len() < 2 {
+        return None;
+    }
+
+    thread_counts.iter().map(|n| 2 + n.checked_ilog10().unwrap_or_default() as usize + 1).max()
+}
+
+/// Returns the distinct thread counts that benchmarks run with, where 0 means
+/// available parallelism.
+fn thread_counts(threads: &[usize]) -> Vec<usize> {
+    let mut thread_counts: Vec<usize> = threads
+        .iter()
+        .map(|&n| if n == 0 { crate::util::known_parallelism().get() } else { n })
+        .collect();
+
+    thread_counts.sort_unstable();
+    thread_counts.dedup();
+    thread_counts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::OnceLock;
+
+    use super::*;
+    use crate::entry::{BenchEntry, BenchEntryRunner, EntryConst, EntryType};
+
+    static BENCH: BenchEntry = BenchEntry {
+        meta: EntryMeta {
+            display_name: "bench",
+            raw_name: "bench",
+            module_path: "suite::module",
+            location: EntryLocation { file: "lib.rs", line: 1, col: 1 },
+            custom_meta: &[],
+            get_bench_options: None,
+            cached_bench_options: OnceLock::new(),
+        },
+        bench: BenchEntryRunner::Plain(|_| {}),
+    };
+
+    static GROUP: GroupEntry = GroupEntry {
+        meta: EntryMeta {
+            display_name: "group",
+            raw_name: "group",
+            module_path: "suite",
+            location: EntryLocation { file: "lib.rs", line: 2, col: 1 },
+            custom_meta: &[],
+            get_bench_options: None,
+            cached_bench_options: OnceLock::new(),
+        },
+        generic_benches: None,
+    };
+
+    static TYPE_BENCH: GenericBenchEntry = GenericBenchEntry {
+        group: &GROUP,
+        bench: BenchEntryRunner::Plain(|_| {}),
+        ty: Some(EntryType::new::<String>()),
+        const_value: None,
+    };
+
+    static CONST_BENCH: GenericBenchEntry = GenericBenchEntry {
+        group: &GROUP,
+        bench: BenchEntryRunner::Plain(|_| {}),
+        ty: None,
+        const_value: Some(EntryConst::new(&64)),
+    };
+
+    /// Returns the names of leaves that are kept for any of `names`, with their
+    /// kept arguments.
+    fn retain_params(names: &[&str]) -> Vec<String> {
+        let mut tree = vec![
+            EntryTree::Parent {
+                raw_name: "group",
+                group: Some(&GROUP),
+                children: vec![
+                    EntryTree::Leaf { entry: AnyBenchEntry::GenericBench(&TYPE_BENCH), args: None },
+                    EntryTree::Leaf {
+                        entry: AnyBenchEntry::GenericBench(&CONST_BENCH),
+                        args: None,
+                    },
+                ],
+            },
+            EntryTree::Leaf {
+                entry: AnyBenchEntry::Bench(&BENCH),
+                args: Some(vec![&"1", &"64", &"4096"]),
+            },
+        ];
+
+        EntryTree::retain_params(&mut tree, &|name| names.contains(&name));
+
+        fn leaves(tree: &[EntryTree], result: &mut Vec<String>) {
+            for subtree in tree {
+                match subtree {
+                    EntryTree::Parent { children, .. } => leaves(children, result),
+                    EntryTree::Leaf { args: None, .. } => {
+                        result.push(subtree.display_name().to_owned());
+                    }
+                    EntryTree::Leaf { args: Some(args), .. } => {
+                        let args: Vec<&str> = args.iter().map(|&&arg| arg).collect();
+                        result.push(format!("{}({})", subtree.display_name(), args.join(",")));
+                    }
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        leaves(&tree, &mut result);
+        result
+    }
+
+    /// A leaf is kept if any name matches its type, `const`, or any of its
+    /// arguments, and only the matching arguments are kept.
+    #[test]
+    fn retain_params_union() {
+        assert_eq!(retain_params(&[]), Vec::<String>::new());
+        assert_eq!(retain_params(&["String"]), ["String"]);
+        assert_eq!(retain_params(&["alloc::string::String"]), ["String"]);
+        assert_eq!(retain_params(&["64"]), ["64", "bench(64)"]);
+        assert_eq!(retain_params(&["1", "4096"]), ["bench(1,4096)"]);
+        assert_eq!(retain_params(&["String", "64", "1"]), ["String", "64", "bench(1,64)"]);
+        assert_eq!(retain_params(&["256"]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn max_name_span_collapsed() {
+        let tree = EntryTree::from_benches([AnyBenchEntry::Bench(&BENCH)]);
+
+        // `╰─ ╰─ bench`
+        assert_eq!(EntryTree::max_name_span(&tree, usize::MAX, None), 11);
+
+        // `╰─ module::bench`, which is wider than the indentation it replaces.
+        assert_eq!(EntryTree::max_name_span(&tree, 1, None), 16);
+    }
+}
@@ -0,0 +1,83 @@
+//! Persists benchmarks that failed in the last run for `--rerun-failed`.
+//!
+//! A benchmark fails if it panics or if it regressed against `--baseline`.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::util;
+
+/// Returns the path of the file listing failed benchmarks, one per line.
+fn record_path() -> Option<PathBuf> {
+    util::exe_data_path(".divan-failed", false)
+}
+
+/// Returns the paths of benchmarks that failed in the last run.
+pub(crate) fn load() -> Vec<String> {
+    let Some(contents) = record_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+
+    contents.lines().filter(|line| !line.is_empty()).map(str::to_owned).collect()
+}
+
+/// Records that the benchmark at `entry_path` failed.
+pub(crate) fn record(entry_path: &str) {
+    let result = record_path().ok_or(io::ErrorKind::NotFound.into()).and_then(|path| {
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{entry_path}")
+    });
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to record failure of '{entry_path}' ({error})");
+    }
+}
+
+/// Forgets about failures of benchmarks for which `will_run` returns `true`,
+/// so that only benchmarks that fail again are recorded.
+///
+/// Failures of other benchmarks, such as those excluded by filters, are kept.
+pub(crate) fn start_recording(will_run: impl Fn(&str) -> bool) {
+    let Some(path) = record_path() else {
+        return;
+    };
+
+    let result = match fs::read_to_string(&path) {
+        Ok(contents) => fs::write(&path, retain_lines(&contents, |line| !will_run(line))),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    };
+
+    if let Err(error) = result {
+        eprintln!("warning: Failed to update failed benchmarks ({error})");
+    }
+}
+
+/// Returns the distinct non-empty lines of `contents` for which `keep` returns
+/// `true`.
+fn retain_lines(contents: &str, keep: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in contents.lines() {
+        if !line.is_empty() && !lines.contains(&line) && keep(line) {
+            lines.push(line);
+        }
+    }
+
+    lines.into_iter().flat_map(|line| [line, "\n"]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn retain_lines() {
+        let contents = "a\nb::c\n\na\nd\n";
+
+        assert_eq!(super::retain_lines(contents, |line| line != "b::c"), "a\nd\n");
+        assert_eq!(super::retain_lines(contents, |_| false), "");
+        assert_eq!(super::retain_lines("", |_| true), "");
+    }
+}
@@ -0,0 +1,286 @@
+//! Summary grids of benchmarks over both types and arguments.
+//!
+//! Each grid has a row per type and a column per argument, so that comparison
+//! matrices do not have to be assembled by hand from the tree output.
+
+use std::collections::HashMap;
+
+use crate::{
+    config::{GridFormat, GridMetric, TableStyle},
+    stats::Stats,
+    time::FineDuration,
+};
+
+/// Summary grids of one time statistic.
+pub(crate) struct Grids {
+    metric: GridMetric,
+    grids: Vec<Grid>,
+}
+
+struct Grid {
+    /// The path of the benchmark, excluding type and argument names.
+    path: String,
+
+    types: Vec<String>,
+    args: Vec<String>,
+
+    /// Values keyed by type and argument index.
+    cells: HashMap<(usize, usize), FineDuration>,
+}
+
+impl Grids {
+    pub fn new(metric: GridMetric) -> Self {
+        Self { metric, grids: Vec::new() }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.grids.is_empty()
+    }
+
+    /// Records the statistics of the benchmark at `path` for `type_name` and
+    /// `arg_name`.
+    pub fn record(&mut self, path: &str, type_name: &str, arg_name: &str, stats: &Stats) {
+        let value = match self.metric {
+            GridMetric::Fastest => stats.time.fastest,
+            GridMetric::Slowest => stats.time.slowest,
+            GridMetric::Median => stats.time.median,
+            GridMetric::Mean => stats.time.mean,
+        };
+
+        let grid = match self.grids.iter().position(|grid| grid.path == path) {
+            Some(index) => &mut self.grids[index],
+            None => {
+                self.grids.push(Grid {
+                    path: path.to_owned(),
+                    types: Vec::new(),
+                    args: Vec::new(),
+                    cells: HashMap::new(),
+                });
+                self.grids.last_mut().unwrap()
+            }
+        };
+
+        let type_index = index_of(&mut grid.types, type_name);
+        let arg_index = index_of(&mut grid.args, arg_name);
+
+        grid.cells.insert((type_index, arg_index), value);
+    }
+
+    /// Formats all grids, each followed by an empty line.
+    pub fn format(&self, format: GridFormat, style: TableStyle, sig_figs: usize) -> String {
+        let mut output = String::new();
+
+        for grid in &self.grids {
+            match format {
+                GridFormat::Table => grid.write_table(&mut output, self.metric, style, sig_figs),
+                GridFormat::Markdown => grid.write_markdown(&mut output, self.metric, sig_figs),
+                GridFormat::Csv => grid.write_csv(&mut output, self.metric),
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+/// Returns the index of `name` in `names`, appending it if missing.
+fn index_of(names: &mut Vec<String>, name: &str) -> usize {
+    match names.iter().position(|n| n == name) {
+        Some(index) => index,
+        None => {
+            names.push(name.to_owned());
+            names.len() - 1
+        }
+    }
+}
+
+impl Grid {
+    /// Returns the rows of cells, starting with a header of argument names
+    /// after an empty corner cell.
+    fn rows(&self, format_value: impl Fn(FineDuration) -> String) -> Vec<Vec<String>> {
+        let header = std::iter::once(String::new()).chain(self.args.iter().cloned()).collect();
+
+        let rows = self.types.iter().enumerate().map(|(type_index, type_name)| {
+            let values = (0..self.args.len()).map(|arg_index| {
+                self.cells.get(&(type_index, arg_index)).map(|&value| format_value(value))
+            });
+
+            std::iter::once(type_name.clone())
+                .chain(values.map(Option::unwrap_or_default))
+                .collect()
+        });
+
+        std::iter::once(header).chain(rows).collect()
+    }
+
+    fn write_table(
+        &self,
+        output: &mut String,
+        metric: GridMetric,
+        style: TableStyle,
+        sig_figs: usize,
+    ) {
+        let rows = self.rows(|value| format!("{value:.sig_figs$}"));
+
+        let mut widths = vec![0; self.args.len() + 1];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        output.push_str(&format!("{} ({})\n", self.path, metric.name()));
+
+        for row in &rows {
+            let mut line = String::new();
+
+            // Missing cells at the end of a row are left out entirely.
+            let len = row.iter().rposition(|cell| !cell.is_empty()).map_or(0, |i| i + 1);
+
+            for (i, (cell, &width)) in row[..len].iter().zip(&widths).enumerate() {
+                if i != 0 {
+                    line.push_str(style.column_separator());
+                }
+                line.push_str(&style.units(&format!("{cell:width$}")));
+            }
+
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+    }
+
+    fn write_markdown(&self, output: &mut String, metric: GridMetric, sig_figs: usize) {
+        let escape = |cell: &str| cell.replace('|', "\\|");
+
+        output.push_str(&format!("**`{}`** ({})\n\n", self.path, metric.name()));
+
+        for (i, row) in self.rows(|value| format!("{value:.sig_figs$}")).iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let cell = escape(cell);
+
+                // Types are code, and may otherwise be read as HTML tags.
+                if j == 0 && i != 0 {
+                    output.push_str(&format!("| `{cell}` "));
+                } else {
+                    output.push_str(&format!("| {cell} "));
+                }
+            }
+            output.push_str("|\n");
+
+            if i == 0 {
+                output.push_str("|:--");
+                output.push_str(&"|--:".repeat(self.args.len()));
+                output.push_str("|\n");
+            }
+        }
+    }
+
+    fn write_csv(&self, output: &mut String, metric: GridMetric) {
+        let quote = |cell: &str| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_owned()
+            }
+        };
+
+        let mut rows = self.rows(|value| (value.picos as f64 / 1_000.0).to_string());
+        rows[0][0] = format!("{} ({} ns)", self.path, metric.name());
+
+        for row in rows {
+            let row: Vec<String> = row.iter().map(|cell| quote(cell)).collect();
+            output.push_str(&row.join(","));
+            output.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        alloc::{AllocOpMap, AllocTally},
+        stats::StatsSet,
+    };
+
+    fn stats(median_picos: u128) -> Stats {
+        let time = FineDuration { picos: median_picos };
+        let set =
+            |value: f64| StatsSet { fastest: value, slowest: value, median: value, mean: value };
+
+        Stats {
+            sample_count: 1,
+            iter_count: 1,
+            time: StatsSet { fastest: time, slowest: time, median: time, mean: time },
+            total_time: time,
+            alloc_tallies: AllocOpMap::from_fn(|_| AllocTally { count: set(0.0), size: set(0.0) }),
+            counts: Default::default(),
+            run_variance: None,
+            cpu_migrations: None,
+            rejected_count: 0,
+            fastest_iter: None,
+            child_cpu_time: None,
+        }
+    }
+
+    fn grids() -> Grids {
+        let mut grids = Grids::new(GridMetric::Median);
+        assert!(grids.is_empty());
+
+        grids.record("maps::insert", "HashMap<u8, u8>", "1", &stats(1_000));
+        grids.record("maps::insert", "HashMap<u8, u8>", "100", &stats(20_000));
+        grids.record("maps::insert", "Vec", "1", &stats(3_500));
+        grids.record("other", "u8", "x", &stats(1_000));
+
+        grids
+    }
+
+    #[test]
+    fn table() {
+        assert_eq!(
+            grids().format(GridFormat::Table, TableStyle::Ascii, 4),
+            "\
+maps::insert (median)
+                | 1      | 100
+HashMap<u8, u8> | 1 ns   | 20 ns
+Vec             | 3.5 ns
+
+other (median)
+   | x
+u8 | 1 ns
+
+"
+        );
+    }
+
+    #[test]
+    fn markdown() {
+        let output = grids().format(GridFormat::Markdown, TableStyle::default(), 4);
+
+        assert!(output.starts_with(
+            "\
+**`maps::insert`** (median)
+
+|  | 1 | 100 |
+|:--|--:|--:|
+| `HashMap<u8, u8>` | 1 ns | 20 ns |
+| `Vec` | 3.5 ns |  |
+"
+        ));
+    }
+
+    #[test]
+    fn csv() {
+        let output = grids().format(GridFormat::Csv, TableStyle::default(), 4);
+
+        assert!(output.starts_with(
+            "\
+maps::insert (median ns),1,100
+\"HashMap<u8, u8>\",1,20
+Vec,3.5,
+"
+        ));
+    }
+}
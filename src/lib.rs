@@ -10,14 +10,22 @@
 #[path = "private.rs"]
 pub mod __private;
 
+mod aggregate;
 mod alloc;
+mod baseline;
 mod bench;
+mod budget;
 mod cli;
 mod compile_fail;
 mod config;
 mod divan;
 mod entry;
+mod failed;
+mod grid;
+mod progress;
+mod samples;
 mod stats;
+mod sweep;
 mod time;
 mod tree_painter;
 mod util;
@@ -116,7 +124,24 @@ pub mod counter;
 pub use std::hint::black_box;
 
 #[doc(inline)]
-pub use crate::{alloc::AllocProfiler, bench::Bencher, divan::Divan};
+pub use crate::{
+    aggregate::Aggregate,
+    alloc::AllocProfiler,
+    bench::Bencher,
+    config::{
+        GridFormat, GridMetric, OutputFormat, SignificanceTest, TableStyle, ThousandsSeparator,
+    },
+    divan::Divan,
+    entry::{EntryInfo, EntryOptions},
+    samples::Samples,
+};
+
+#[doc(inline)]
+#[cfg(feature = "mock_clock")]
+pub use crate::time::MockClock;
+
+#[doc(inline)]
+pub use crate::sweep::{linear, powers_of_two, SweepInt};
 
 /// Runs all registered benchmarks.
 ///
@@ -140,6 +165,32 @@ pub fn main() {
     Divan::from_args().main();
 }
 
+/// Returns which of the given cargo features are enabled for the calling
+/// crate, for passing to [`Divan::features`].
+///
+/// Cargo does not expose the set of enabled features at runtime, so the
+/// features of interest must be listed.
+///
+/// # Examples
+///
+/// ```
+/// let features: Vec<&str> = divan::features!("simd", "std");
+///
+/// assert_eq!(features, Vec::<&str>::new());
+/// ```
+#[macro_export]
+macro_rules! features {
+    ($($feature:tt),* $(,)?) => {{
+        let mut features: ::std::vec::Vec<&'static str> = ::std::vec::Vec::new();
+        $(
+            if ::std::cfg!(feature = $feature) {
+                features.push($feature);
+            }
+        )*
+        features
+    }};
+}
+
 /// [`black_box`] + [`drop`] convenience function.
 ///
 /// # Examples
@@ -283,8 +334,14 @@ pub fn black_box_drop<T>(dummy: T) {
 ///     - [`items_count`]
 /// - [`min_time`]
 /// - [`max_time`]
+/// - [`max_sample_time`]
+/// - [`warm_up_time`]
 /// - [`skip_ext_time`]
+/// - [`fastest_iter`]
+/// - [`child_cpu_time`]
+/// - [`priority`]
 /// - [`ignore`]
+/// - [`meta`]
 ///
 /// ## `name`
 /// [`name`]: #name
@@ -396,6 +453,20 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// Size sweeps can be declared with [`powers_of_two`] and [`linear`]:
+///
+/// ```
+/// #[divan::bench(args = divan::powers_of_two(1..=20))]
+/// fn fill_vec(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+///
+/// #[divan::bench(args = divan::linear(0..1000, 100))]
+/// fn fill_array(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+/// ```
+///
 /// For convenience, common string types are coerced to [`&str`](primitive@str):
 ///
 /// ```
@@ -691,6 +762,19 @@ pub fn black_box_drop<T>(dummy: T) {
 /// Convenience shorthand for
 /// <code>[counter](#counters) = [ItemsCount](counter::ItemsCount)::from(n)</code>.
 ///
+/// ### `counter = arg`
+///
+/// When benchmarking over integer [`args`] that represent input sizes,
+/// `counter = arg` sets an [`ItemsCount`](counter::ItemsCount) from each
+/// argument's value:
+///
+/// ```
+/// #[divan::bench(args = [1, 10, 100], counter = arg)]
+/// fn fill_vec(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+/// ```
+///
 /// ## `min_time`
 /// [`min_time`]: #min_time
 ///
@@ -714,7 +798,8 @@ pub fn black_box_drop<T>(dummy: T) {
 /// ```
 ///
 /// For convenience, [`min_time`] can also be set with seconds as [`u64`] or
-/// [`f64`]. Invalid values will cause a panic at runtime.
+/// [`f64`], or with a human-readable [`&str`](prim@str) like `"2.5ms"` or
+/// `"1m30s"`. Invalid values will cause a panic at runtime.
 ///
 /// ```
 /// #[divan::bench(min_time = 2)]
@@ -728,6 +813,12 @@ pub fn black_box_drop<T>(dummy: T) {
 ///     // ...
 ///     # 0
 /// }
+///
+/// #[divan::bench(min_time = "500ms")]
+/// fn str_time() -> i32 {
+///     // ...
+///     # 0
+/// }
 /// ```
 ///
 /// ## `max_time`
@@ -756,7 +847,8 @@ pub fn black_box_drop<T>(dummy: T) {
 /// ```
 ///
 /// For convenience, like [`min_time`], [`max_time`] can also be set with
-/// seconds as [`u64`] or [`f64`]. Invalid values will cause a panic at runtime.
+/// seconds as [`u64`] or [`f64`], or with a human-readable [`&str`](prim@str).
+/// Invalid values will cause a panic at runtime.
 ///
 /// ```
 /// #[divan::bench(max_time = 8)]
@@ -770,6 +862,51 @@ pub fn black_box_drop<T>(dummy: T) {
 ///     // ...
 ///     # 0
 /// }
+///
+/// #[divan::bench(max_time = "1m30s")]
+/// fn str_time() -> i32 {
+///     // ...
+///     # 0
+/// }
+/// ```
+///
+/// ## `max_sample_time`
+/// [`max_sample_time`]: #max_sample_time
+///
+/// The maximum time of a single sample can be set via the [`max_sample_time`]
+/// option. This may be overridden at runtime using either the
+/// `DIVAN_MAX_SAMPLE_TIME` environment variable or `--max-sample-time` CLI
+/// argument.
+///
+/// If a sample takes longer, subsequent samples run fewer iterations. If a
+/// single iteration takes longer, sampling stops early with a warning. This
+/// prevents pathological inputs from making a sweep over [`args`] take hours.
+///
+/// ```
+/// #[divan::bench(args = [1, 10, 100], max_sample_time = "100ms")]
+/// fn fib(n: u64) -> u64 {
+///     // ...
+///     # n
+/// }
+/// ```
+///
+/// ## `warm_up_time`
+/// [`warm_up_time`]: #warm_up_time
+///
+/// CPUs may be in a low-power state with a reduced clock speed when a
+/// benchmark starts, which makes its first samples slower. The
+/// [`warm_up_time`] option spins the CPU for the given time before sampling
+/// begins, which is not counted towards [`min_time`] or [`max_time`]. When
+/// benchmarking with multiple [`threads`], each benchmarking thread spins its
+/// own CPU right before its first sample. This may be overridden at runtime
+/// using either the `DIVAN_WARM_UP_TIME` environment variable or
+/// `--warm-up-time` CLI argument.
+///
+/// ```
+/// #[divan::bench(warm_up_time = "50ms")]
+/// fn bench() {
+///     // ...
+/// }
 /// ```
 ///
 /// ## `skip_ext_time`
@@ -806,6 +943,78 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// ## `fastest_iter`
+/// [`fastest_iter`]: #fastest_iter
+///
+/// The fastest time is the fastest average over the iterations of a sample,
+/// which hides how fast a single iteration can be. Enabling the
+/// [`fastest_iter`] option also times single iterations after sampling, within
+/// the remaining [`max_time`], to report the fastest one below the table row.
+/// This approximates the cost of the function without interference, such as
+/// from interrupts or cache misses, but is limited by timer precision. This may
+/// be overridden at runtime using either the `DIVAN_FASTEST_ITER` environment
+/// variable or `--fastest-iter` CLI argument.
+///
+/// ```
+/// #[divan::bench(fastest_iter)]
+/// fn bench() {
+///     // ...
+/// }
+/// ```
+///
+/// This option only applies to single-threaded benchmarks. It is skipped if a
+/// single iteration exceeds [`max_sample_time`].
+///
+/// ## `child_cpu_time`
+/// [`child_cpu_time`]: #child_cpu_time
+///
+/// Time spent in subprocesses, such as with
+/// [`Bencher::bench_command`], is only partly reflected by the wall time of
+/// a benchmark. Enabling the [`child_cpu_time`] option also reports the mean
+/// user and system CPU time of child processes per iteration, which is
+/// meaningful for multi-process pipelines. This may be overridden at runtime
+/// using either the `DIVAN_CHILD_CPU_TIME` environment variable or
+/// `--child-cpu-time` CLI argument.
+///
+/// ```
+/// use std::process::Command;
+///
+/// #[divan::bench(child_cpu_time)]
+/// fn bench(bencher: divan::Bencher) {
+///     bencher.bench_command(Command::new("true"));
+/// }
+/// ```
+///
+/// Only children that have exited and been waited on are counted, via
+/// `getrusage(RUSAGE_CHILDREN)`. This option is only supported on Unix. Since
+/// these counters are process-wide, benchmarks with this option are measured
+/// on their own rather than concurrently with `--jobs`.
+///
+/// ## `priority`
+/// [`priority`]: #priority
+///
+/// Benchmarks run in the order that they are displayed, which may be changed
+/// with `--sort` and `--sortr`. The [`priority`] option instead runs a
+/// benchmark before all others with lower priority, which is 0 by default, so
+/// that important benchmarks fail or finish early in a long suite. Results are
+/// still printed in display order once all prioritized benchmarks finish, since
+/// each result is drawn at its position in the tree.
+///
+/// ```
+/// #[divan::bench(priority = 10)]
+/// fn important() {
+///     // ...
+/// }
+///
+/// #[divan::bench(priority = 1)]
+/// fn less_important() {
+///     // ...
+/// }
+/// ```
+///
+/// With `--jobs`, single-threaded benchmarks start in order of descending
+/// priority, so prioritizing slow benchmarks also shortens the total run.
+///
 /// ## `ignore`
 /// [`ignore`]: #ignore
 ///
@@ -854,6 +1063,25 @@ pub fn black_box_drop<T>(dummy: T) {
 /// }
 /// ```
 ///
+/// ## `meta`
+/// [`meta`]: #meta
+///
+/// Custom key/value pairs can be attached to a benchmark via the [`meta`]
+/// option. These are shown below the benchmark's statistics, saved with
+/// `--save-baseline`, and included in `--list --format json` and
+/// `--format terse` output, so that tooling can route and attribute results,
+/// such as to an owning team.
+///
+/// ```
+/// #[divan::bench(meta("team" = "storage", "ticket" = "PERF-42"))]
+/// fn bench() {
+///     // ...
+/// }
+/// ```
+///
+/// Keys must not contain `=`, tabs, or line breaks. Values are inherited from parent [`#[divan::bench_group]`](macro@bench_group)
+/// options, and values for the same key override parent values.
+///
 /// [`Any`]: std::any::Any
 /// [`Duration`]: std::time::Duration
 /// [available parallelism]: std::thread::available_parallelism
@@ -950,8 +1178,14 @@ pub use divan_macros::bench;
 ///     - [`items_count`]
 /// - [`min_time`]
 /// - [`max_time`]
+/// - [`max_sample_time`]
+/// - [`warm_up_time`]
 /// - [`skip_ext_time`]
+/// - [`fastest_iter`]
+/// - [`child_cpu_time`]
+/// - [`priority`]
 /// - [`ignore`]
+/// - [`meta`]
 ///
 /// ## `name`
 /// [`name`]: #name
@@ -1153,7 +1387,8 @@ pub use divan_macros::bench;
 /// ```
 ///
 /// For convenience, [`min_time`] can also be set with seconds as [`u64`] or
-/// [`f64`]. Invalid values will cause a panic at runtime.
+/// [`f64`], or with a human-readable [`&str`](prim@str) like `"2.5ms"` or
+/// `"1m30s"`. Invalid values will cause a panic at runtime.
 ///
 /// ```
 /// #[divan::bench_group(min_time = 2)]
@@ -1165,6 +1400,11 @@ pub use divan_macros::bench;
 /// mod float_secs {
 ///     // ...
 /// }
+///
+/// #[divan::bench_group(min_time = "500ms")]
+/// mod str_time {
+///     // ...
+/// }
 /// ```
 ///
 /// ## `max_time`
@@ -1195,7 +1435,8 @@ pub use divan_macros::bench;
 /// ```
 ///
 /// For convenience, like [`min_time`], [`max_time`] can also be set with
-/// seconds as [`u64`] or [`f64`]. Invalid values will cause a panic at runtime.
+/// seconds as [`u64`] or [`f64`], or with a human-readable [`&str`](prim@str).
+/// Invalid values will cause a panic at runtime.
 ///
 /// ```
 /// #[divan::bench_group(max_time = 8)]
@@ -1207,6 +1448,51 @@ pub use divan_macros::bench;
 /// mod float_secs {
 ///     // ...
 /// }
+///
+/// #[divan::bench_group(max_time = "1m30s")]
+/// mod str_time {
+///     // ...
+/// }
+/// ```
+///
+/// ## `max_sample_time`
+/// [`max_sample_time`]: #max_sample_time
+///
+/// The maximum time of a single sample for each benchmark can be set via the
+/// [`max_sample_time`] option. This may be overridden at runtime using either
+/// the `DIVAN_MAX_SAMPLE_TIME` environment variable or `--max-sample-time` CLI
+/// argument.
+///
+/// If a sample takes longer, subsequent samples run fewer iterations. If a
+/// single iteration takes longer, sampling stops early with a warning.
+///
+/// ```
+/// #[divan::bench_group(max_sample_time = "100ms")]
+/// mod math {
+///     #[divan::bench]
+///     fn add() -> i32 {
+///         // ...
+///         # 0
+///     }
+/// }
+/// ```
+///
+/// ## `warm_up_time`
+/// [`warm_up_time`]: #warm_up_time
+///
+/// The CPU is spun for the given time before sampling each benchmark in the
+/// group, so that first samples are not taken while it is in a low-power
+/// state. This may be overridden at runtime using either the
+/// `DIVAN_WARM_UP_TIME` environment variable or `--warm-up-time` CLI argument.
+///
+/// ```
+/// #[divan::bench_group(warm_up_time = "50ms")]
+/// mod group {
+///     #[divan::bench]
+///     fn bench() {
+///         // ...
+///     }
+/// }
 /// ```
 ///
 /// ## `skip_ext_time`
@@ -1246,6 +1532,60 @@ pub use divan_macros::bench;
 /// }
 /// ```
 ///
+/// ## `fastest_iter`
+/// [`fastest_iter`]: #fastest_iter
+///
+/// Times single iterations of benchmarks in the group after sampling, to report
+/// the fastest one alongside the fastest sample average. This may be
+/// overridden at runtime using either the `DIVAN_FASTEST_ITER` environment
+/// variable or `--fastest-iter` CLI argument.
+///
+/// ```
+/// #[divan::bench_group(fastest_iter)]
+/// mod group {
+///     #[divan::bench]
+///     fn bench() {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// ## `child_cpu_time`
+/// [`child_cpu_time`]: #child_cpu_time
+///
+/// Also reports the CPU time of child processes per iteration for benchmarks
+/// in the group. This may be overridden at runtime using either the
+/// `DIVAN_CHILD_CPU_TIME` environment variable or `--child-cpu-time` CLI
+/// argument.
+///
+/// ```
+/// #[divan::bench_group(child_cpu_time)]
+/// mod group {
+///     #[divan::bench]
+///     fn bench(bencher: divan::Bencher) {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// ## `priority`
+/// [`priority`]: #priority
+///
+/// Benchmarks in the group run before all others with lower priority, which is
+/// 0 by default, instead of in display order. Like other options, this can be
+/// overridden by each benchmark. Results are still printed in display order
+/// once all prioritized benchmarks finish.
+///
+/// ```
+/// #[divan::bench_group(priority = 10)]
+/// mod smoke {
+///     #[divan::bench]
+///     fn startup() {
+///         // ...
+///     }
+/// }
+/// ```
+///
 /// ## `ignore`
 /// [`ignore`]: #ignore
 ///
@@ -1302,5 +1642,27 @@ pub use divan_macros::bench;
 /// }
 /// ```
 ///
+/// ## `meta`
+/// [`meta`]: #meta
+///
+/// Custom key/value pairs can be attached to all benchmarks in a group via the
+/// [`meta`] option. These are shown below the statistics of each benchmark,
+/// saved with `--save-baseline`, and included in `--list --format json` and
+/// `--format terse` output, so that tooling can route and attribute results,
+/// such as to an owning team.
+///
+/// ```
+/// #[divan::bench_group(meta("team" = "storage"))]
+/// mod storage {
+///     #[divan::bench(meta("ticket" = "PERF-42"))]
+///     fn bench() {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Values for the same key in child benchmarks and groups override values set
+/// here.
+///
 /// [`Duration`]: std::time::Duration
 pub use divan_macros::bench_group;
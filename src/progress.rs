@@ -0,0 +1,148 @@
+//! Estimated time remaining, shown while benchmarks run.
+//!
+//! The estimate is printed to stderr after the name of the running benchmark,
+//! and then erased before its statistics are printed to stdout. This is only
+//! done if both are terminals, so that redirected output is unaffected.
+
+use std::{
+    io::{IsTerminal, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{config::TableStyle, time::FineDuration};
+
+/// How often the estimate is reprinted.
+const PRINT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tracks progress through a run to estimate the time remaining.
+#[derive(Default)]
+pub(crate) struct Progress {
+    enabled: bool,
+
+    /// The expected number of benchmarks in the run.
+    bench_count: usize,
+
+    /// Determines how units are written.
+    style: TableStyle,
+
+    /// Locked so that benchmarks can run on other threads with `--jobs`,
+    /// although the estimate is then not shown.
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// The number of finished benchmarks.
+    finished_count: usize,
+
+    /// The sum of wall times of finished benchmarks.
+    finished_time: Duration,
+
+    /// When the current benchmark started.
+    bench_start: Option<Instant>,
+
+    /// When the estimate was last printed, or `None` if it is not visible.
+    last_print: Option<Instant>,
+}
+
+impl Progress {
+    /// Returns progress for a run of `bench_count` benchmarks, which is only
+    /// shown if stdout and stderr are terminals.
+    pub fn new(bench_count: usize, style: TableStyle) -> Self {
+        let enabled = std::io::stdout().is_terminal() && std::io::stderr().is_terminal();
+
+        Self { enabled, bench_count, style, ..Self::default() }
+    }
+
+    /// Returns `true` if the estimate is shown.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Marks the start of a benchmark.
+    pub fn start_bench(&self) {
+        if self.enabled {
+            self.state().bench_start = Some(Instant::now());
+        }
+    }
+
+    /// Updates the estimate given the fraction of the current benchmark that is
+    /// done, in `0..=1`.
+    pub fn update_bench(&self, done_fraction: f64) {
+        if !self.enabled {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut state = self.state();
+
+        let Some(bench_start) = state.bench_start else {
+            return;
+        };
+
+        if state.last_print.is_some_and(|last| now.duration_since(last) < PRINT_INTERVAL)
+            || done_fraction <= 0.0
+        {
+            return;
+        }
+
+        let bench_elapsed = now.duration_since(bench_start).as_secs_f64();
+        let bench_remaining = bench_elapsed * (1.0 - done_fraction.min(1.0)) / done_fraction;
+
+        // Assume that remaining benchmarks take as long as previous ones did on
+        // average, or as long as the current one if none have finished.
+        let finished_count = state.finished_count;
+        let average_bench_time = if finished_count == 0 {
+            bench_elapsed + bench_remaining
+        } else {
+            state.finished_time.as_secs_f64() / finished_count as f64
+        };
+
+        let remaining_count = self.bench_count.saturating_sub(finished_count + 1);
+        let run_remaining = bench_remaining + remaining_count as f64 * average_bench_time;
+
+        let to_duration = |secs: f64| FineDuration::from(Duration::from_secs_f64(secs.max(0.0)));
+
+        // Save the cursor, print, clear stale characters, and then restore the
+        // cursor so that statistics are printed after the name.
+        let estimate = format!(
+            "ETA {:.2} (all: {:.2})",
+            to_duration(bench_remaining),
+            to_duration(run_remaining),
+        );
+
+        let mut stderr = std::io::stderr().lock();
+        _ = write!(stderr, "\x1b7{}\x1b[K\x1b8", self.style.units(&estimate));
+        _ = stderr.flush();
+
+        state.last_print = Some(now);
+    }
+
+    /// Marks the end of the current benchmark and erases the estimate.
+    pub fn finish_bench(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let mut state = self.state();
+
+        let Some(bench_start) = state.bench_start.take() else {
+            return;
+        };
+
+        state.finished_count += 1;
+        state.finished_time += bench_start.elapsed();
+
+        if state.last_print.take().is_some() {
+            let mut stderr = std::io::stderr().lock();
+            _ = write!(stderr, "\x1b[K");
+            _ = stderr.flush();
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
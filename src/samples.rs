@@ -0,0 +1,78 @@
+//! Recorded samples for analysis outside of Divan.
+
+use std::time::Duration;
+
+use crate::counter::{Counter, KnownCounterKind};
+
+/// Samples recorded over one run of a benchmark.
+///
+/// These are passed to the callback of
+/// [`Divan::on_samples`](crate::Divan::on_samples) so that custom estimators
+/// can be applied without running benchmarks again. Samples rejected as clock
+/// anomalies are excluded.
+///
+/// For multi-threaded benchmarks, each thread's sample is included separately.
+#[derive(Clone, Debug)]
+pub struct Samples {
+    pub(crate) path: String,
+    pub(crate) threads: usize,
+    pub(crate) sample_size: u32,
+    pub(crate) durations: Vec<Duration>,
+    pub(crate) counts: [Option<Vec<u64>>; KnownCounterKind::COUNT],
+}
+
+impl Samples {
+    /// Returns the full path of the benchmark, including any argument name.
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the number of threads that ran the benchmark.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Returns the number of iterations within each sample.
+    #[inline]
+    pub fn sample_size(&self) -> u32 {
+        self.sample_size
+    }
+
+    /// Returns the total time of each sample.
+    ///
+    /// Per-iteration times are these divided by
+    /// [`sample_size`](Self::sample_size).
+    #[inline]
+    pub fn durations(&self) -> &[Duration] {
+        &self.durations
+    }
+
+    /// Returns the per-iteration count of `C` for each sample, or `None` if
+    /// the benchmark does not count `C`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use divan::counter::BytesCount;
+    ///
+    /// fn bytes_per_sec(samples: &divan::Samples) -> Option<Vec<f64>> {
+    ///     let counts = samples.counts::<BytesCount>()?;
+    ///     let sample_size = f64::from(samples.sample_size());
+    ///
+    ///     Some(
+    ///         samples
+    ///             .durations()
+    ///             .iter()
+    ///             .zip(counts)
+    ///             .map(|(duration, &count)| count as f64 * sample_size / duration.as_secs_f64())
+    ///             .collect(),
+    ///     )
+    /// }
+    /// ```
+    #[inline]
+    pub fn counts<C: Counter>(&self) -> Option<&[u64]> {
+        self.counts[KnownCounterKind::of::<C>() as usize].as_deref()
+    }
+}
@@ -0,0 +1,374 @@
+//! Comparisons of sample distributions against a baseline.
+
+use crate::{config::SignificanceTest, stats::StatsSet, time::FineDuration};
+
+/// The p-value below which a change is considered significant.
+pub(crate) const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Comparison of a benchmark's per-iteration sample times against a baseline.
+pub(crate) struct Comparison {
+    /// Relative change of each statistic, where `0.1` is 10% slower.
+    pub time_change: StatsSet<f64>,
+
+    /// Test of whether the distribution of sample times changed.
+    pub test: TestResult,
+}
+
+impl Comparison {
+    /// Compares `current` against `baseline`, or returns `None` if either has
+    /// no samples.
+    pub fn new(
+        current: &[FineDuration],
+        baseline: &[FineDuration],
+        test: SignificanceTest,
+    ) -> Option<Self> {
+        let mut current = current.to_vec();
+        let mut baseline = baseline.to_vec();
+
+        current.sort_unstable();
+        baseline.sort_unstable();
+
+        let current_stats = sorted_stats(&current)?;
+        let baseline_stats = sorted_stats(&baseline)?;
+
+        let change = |current: f64, baseline: f64| -> f64 {
+            if baseline == 0.0 {
+                0.0
+            } else {
+                (current - baseline) / baseline
+            }
+        };
+
+        Some(Self {
+            time_change: StatsSet {
+                fastest: change(current_stats.fastest, baseline_stats.fastest),
+                slowest: change(current_stats.slowest, baseline_stats.slowest),
+                median: change(current_stats.median, baseline_stats.median),
+                mean: change(current_stats.mean, baseline_stats.mean),
+            },
+            test: match test {
+                SignificanceTest::Ks => TestResult::ks(&current, &baseline),
+                SignificanceTest::MannWhitney => TestResult::mann_whitney(&current, &baseline),
+            },
+        })
+    }
+
+    /// Returns `true` if sample times changed significantly and the median got
+    /// slower.
+    pub fn is_regression(&self) -> bool {
+        self.test.is_significant() && self.time_change.median > 0.0
+    }
+}
+
+/// Computes statistics in picoseconds over sorted samples.
+fn sorted_stats(sorted: &[FineDuration]) -> Option<StatsSet<f64>> {
+    let picos = |sample: &FineDuration| sample.picos as f64;
+
+    let middle = crate::util::slice_middle(sorted);
+    if middle.is_empty() {
+        return None;
+    }
+
+    Some(StatsSet {
+        fastest: picos(sorted.first()?),
+        slowest: picos(sorted.last()?),
+        median: middle.iter().map(picos).sum::<f64>() / middle.len() as f64,
+        mean: sorted.iter().map(picos).sum::<f64>() / sorted.len() as f64,
+    })
+}
+
+/// Returns `true` if `samples` are in ascending order.
+fn is_sorted(samples: &[FineDuration]) -> bool {
+    samples.windows(2).all(|pair| pair[0] <= pair[1])
+}
+
+/// The result of a two-sample significance test.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TestResult {
+    pub test: SignificanceTest,
+
+    /// The test statistic: `D` for Kolmogorov–Smirnov and `U` for
+    /// Mann–Whitney.
+    pub statistic: f64,
+
+    /// The asymptotic probability of a statistic at least as extreme if both
+    /// samples came from the same distribution.
+    pub p_value: f64,
+}
+
+impl TestResult {
+    /// Two-sample [Kolmogorov–Smirnov test](https://en.wikipedia.org/wiki/Kolmogorov%E2%80%93Smirnov_test)
+    /// over two sorted non-empty samples.
+    ///
+    /// Unlike comparing means or medians, this detects changes in the shape of
+    /// a distribution, such as a new slow path taken a small fraction of the
+    /// time.
+    pub fn ks(a: &[FineDuration], b: &[FineDuration]) -> Self {
+        debug_assert!(is_sorted(a) && is_sorted(b));
+
+        let (n, m) = (a.len() as f64, b.len() as f64);
+        let (mut i, mut j) = (0, 0);
+        let mut statistic: f64 = 0.0;
+
+        while i < a.len() && j < b.len() {
+            // Step past all values equal to the smallest so that ties do not
+            // inflate the distance.
+            let value = a[i].min(b[j]);
+            while i < a.len() && a[i] == value {
+                i += 1;
+            }
+            while j < b.len() && b[j] == value {
+                j += 1;
+            }
+
+            statistic = statistic.max((i as f64 / n - j as f64 / m).abs());
+        }
+
+        let effective_len = (n * m / (n + m)).sqrt();
+        let lambda = (effective_len + 0.12 + 0.11 / effective_len) * statistic;
+
+        Self { test: SignificanceTest::Ks, statistic, p_value: kolmogorov_survival(lambda) }
+    }
+
+    /// Two-sided [Mann–Whitney U test](https://en.wikipedia.org/wiki/Mann%E2%80%93Whitney_U_test)
+    /// over two sorted non-empty samples.
+    ///
+    /// This is non-parametric, so it does not assume that timings are
+    /// normally distributed, and it is sensitive to shifts in the median.
+    pub fn mann_whitney(a: &[FineDuration], b: &[FineDuration]) -> Self {
+        debug_assert!(is_sorted(a) && is_sorted(b));
+
+        let (n, m) = (a.len() as f64, b.len() as f64);
+        let (mut i, mut j) = (0, 0);
+
+        // Sum of ranks of `a` in the combined sample, with tied values taking
+        // the average of their ranks.
+        let mut a_rank_sum = 0.0;
+        let mut tie_sum = 0.0;
+
+        while i < a.len() || j < b.len() {
+            let value = match (a.get(i), b.get(j)) {
+                (Some(&x), Some(&y)) => x.min(y),
+                (Some(&x), None) => x,
+                (None, Some(&y)) => y,
+                (None, None) => unreachable!(),
+            };
+
+            let lowest_rank = (i + j + 1) as f64;
+
+            let a_ties = a[i..].iter().take_while(|&&x| x == value).count();
+            let b_ties = b[j..].iter().take_while(|&&y| y == value).count();
+            i += a_ties;
+            j += b_ties;
+
+            let ties = (a_ties + b_ties) as f64;
+            let mean_rank = lowest_rank + (ties - 1.0) / 2.0;
+
+            a_rank_sum += a_ties as f64 * mean_rank;
+            tie_sum += ties * ties * ties - ties;
+        }
+
+        let statistic = a_rank_sum - n * (n + 1.0) / 2.0;
+
+        let total = n + m;
+        let mean = n * m / 2.0;
+        let variance = n * m / 12.0 * ((total + 1.0) - tie_sum / (total * (total - 1.0)));
+
+        let p_value = if variance <= 0.0 {
+            // All values are tied.
+            1.0
+        } else {
+            // Normal approximation with continuity correction.
+            let z = ((statistic - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+            erfc(z / std::f64::consts::SQRT_2).min(1.0)
+        };
+
+        Self { test: SignificanceTest::MannWhitney, statistic, p_value }
+    }
+
+    /// Returns `true` if the distributions differ significantly.
+    pub fn is_significant(&self) -> bool {
+        self.p_value < SIGNIFICANCE_LEVEL
+    }
+}
+
+/// The complementary error function, with fractional error below 1.2e-7.
+///
+/// This is the Chebyshev fitting of Numerical Recipes' `erfcc`.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+
+    let poly = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, &c| acc * t + c);
+
+    let result = t * (-z * z + poly).exp();
+
+    if x >= 0.0 {
+        result
+    } else {
+        2.0 - result
+    }
+}
+
+/// The survival function of the Kolmogorov distribution:
+/// `2 Σ (-1)^(k-1) e^(-2 k² λ²)`.
+fn kolmogorov_survival(lambda: f64) -> f64 {
+    let exp_factor = -2.0 * lambda * lambda;
+
+    let mut sum = 0.0;
+    let mut sign = 2.0;
+    let mut prev_term: f64 = 0.0;
+
+    for k in 1..=100 {
+        let k = k as f64;
+        let term = sign * (exp_factor * k * k).exp();
+        sum += term;
+
+        if term.abs() <= 0.001 * prev_term.abs() || term.abs() <= 1e-8 * sum {
+            return sum.clamp(0.0, 1.0);
+        }
+
+        sign = -sign;
+        prev_term = term;
+    }
+
+    // The series does not converge for tiny distances.
+    1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn durations(picos: impl IntoIterator<Item = u128>) -> Vec<FineDuration> {
+        let mut result: Vec<FineDuration> =
+            picos.into_iter().map(|picos| FineDuration { picos }).collect();
+        result.sort_unstable();
+        result
+    }
+
+    #[test]
+    fn regression() {
+        let compare = |current, baseline| {
+            Comparison::new(&durations(current), &durations(baseline), SignificanceTest::Ks)
+                .unwrap()
+                .is_regression()
+        };
+
+        assert!(compare(101..=200, 1..=100));
+        assert!(!compare(1..=100, 101..=200));
+        assert!(!compare(1..=100, 1..=100));
+    }
+
+    #[test]
+    fn ks_same() {
+        let a = durations(1..=100);
+        let ks = TestResult::ks(&a, &a);
+
+        assert_eq!(ks.statistic, 0.0);
+        assert_eq!(ks.p_value, 1.0);
+        assert!(!ks.is_significant());
+    }
+
+    #[test]
+    fn ks_disjoint() {
+        let ks = TestResult::ks(&durations(1..=100), &durations(101..=200));
+
+        assert_eq!(ks.statistic, 1.0);
+        assert!(ks.p_value < 1e-6);
+        assert!(ks.is_significant());
+    }
+
+    #[test]
+    fn ks_ties() {
+        let a = durations(std::iter::repeat(10).take(50));
+        let b = durations(std::iter::repeat(10).take(40).chain(std::iter::repeat(20).take(10)));
+
+        let ks = TestResult::ks(&a, &b);
+        assert!((ks.statistic - 0.2).abs() < 1e-9);
+    }
+
+    /// Same median, but a slow path is taken 20% of the time.
+    #[test]
+    fn ks_slow_path() {
+        let a = durations(std::iter::repeat(100).take(500));
+        let b = durations(std::iter::repeat(100).take(400).chain(std::iter::repeat(150).take(100)));
+
+        let comparison = Comparison::new(&b, &a, SignificanceTest::Ks).unwrap();
+        assert_eq!(comparison.time_change.median, 0.0);
+        assert!(comparison.test.is_significant());
+    }
+
+    #[test]
+    fn mann_whitney_same() {
+        let a = durations(1..=100);
+        let mw = TestResult::mann_whitney(&a, &a);
+
+        assert_eq!(mw.statistic, 5000.0);
+        assert!(mw.p_value > 0.99);
+        assert!(!mw.is_significant());
+
+        let tied = durations(std::iter::repeat(7).take(10));
+        assert_eq!(TestResult::mann_whitney(&tied, &tied).p_value, 1.0);
+    }
+
+    #[test]
+    fn mann_whitney_shift() {
+        let mw = TestResult::mann_whitney(&durations(11..=60), &durations(1..=50));
+
+        // 51..=60 each beat all 50 values of `b`, and each `x` in 11..=50
+        // beats `x - 1` values and ties with one.
+        assert_eq!(mw.statistic, 500.0 + 1200.0);
+        assert!(mw.is_significant());
+
+        let mw = TestResult::mann_whitney(&durations(101..=200), &durations(1..=100));
+        assert_eq!(mw.statistic, 10_000.0);
+        assert!(mw.p_value < 1e-6);
+    }
+
+    #[test]
+    fn erfc() {
+        #[track_caller]
+        fn test(x: f64, expected: f64) {
+            let actual = super::erfc(x);
+            assert!((actual - expected).abs() < 1e-6, "erfc({x}) = {actual}");
+        }
+
+        test(0.0, 1.0);
+        test(0.5, 0.4795001221869535);
+        test(1.0, 0.15729920705028513);
+        test(2.0, 0.004677734981047266);
+        test(-1.0, 1.8427007929497148);
+    }
+
+    #[test]
+    fn time_change() {
+        let comparison = Comparison::new(
+            &durations([110, 220, 330]),
+            &durations([100, 200, 300]),
+            SignificanceTest::Ks,
+        )
+        .unwrap();
+
+        let StatsSet { fastest, slowest, median, mean } = comparison.time_change;
+        for change in [fastest, slowest, median, mean] {
+            assert!((change - 0.1).abs() < 1e-9, "{change}");
+        }
+
+        assert!(Comparison::new(&[], &durations([1]), SignificanceTest::Ks).is_none());
+    }
+}
@@ -1,14 +1,18 @@
 //! Measurement statistics.
 
 use crate::{
-    alloc::{AllocOpMap, AllocTally},
+    alloc::{AllocOp, AllocOpMap, AllocTally},
     counter::{KnownCounterKind, MaxCountUInt},
     time::FineDuration,
 };
 
+mod compare;
 mod sample;
+mod variance;
 
+pub(crate) use compare::*;
 pub(crate) use sample::*;
+pub(crate) use variance::*;
 
 /// Statistics from samples.
 pub(crate) struct Stats {
@@ -21,21 +25,163 @@ pub(crate) struct Stats {
     /// Timing statistics.
     pub time: StatsSet<FineDuration>,
 
+    /// Total time measured across all samples.
+    pub total_time: FineDuration,
+
     /// Allocation statistics associated with the corresponding samples for
     /// `time`.
     pub alloc_tallies: AllocOpMap<AllocTally<StatsSet<f64>>>,
 
     /// `Counter` counts associated with the corresponding samples for `time`.
     pub counts: [Option<StatsSet<MaxCountUInt>>; KnownCounterKind::COUNT],
+
+    /// Variance decomposition across repeated runs, if there were multiple.
+    pub run_variance: Option<RunVariance>,
+
+    /// The number of times the benchmarking thread migrated between CPUs, if
+    /// the OS exposes it.
+    pub cpu_migrations: Option<u64>,
+
+    /// The number of samples rejected as clock anomalies, which are excluded
+    /// from `sample_count`.
+    pub rejected_count: u32,
+
+    /// The fastest single iteration, if timed with the `fastest_iter` option.
+    pub fastest_iter: Option<FineDuration>,
+
+    /// The mean CPU time of child processes per iteration, if counted with the
+    /// `child_cpu_time` option.
+    pub child_cpu_time: Option<FineDuration>,
 }
 
 impl Stats {
     pub fn get_counts(&self, counter_kind: KnownCounterKind) -> Option<&StatsSet<MaxCountUInt>> {
         self.counts[counter_kind as usize].as_ref()
     }
+
+    /// Returns the number of CPU migrations if they happened in more than 5% of
+    /// samples, which makes outlier samples likely.
+    pub fn many_cpu_migrations(&self) -> Option<u64> {
+        self.cpu_migrations.filter(|&migrations| migrations * 20 > u64::from(self.sample_count))
+    }
+
+    /// Combines statistics from repeated runs of the same benchmark.
+    ///
+    /// The fastest and slowest times are the extremes over all runs, and the
+    /// median time is the median of each run's median. Allocation and counter
+    /// statistics come from the same run as the corresponding time, and means
+    /// are weighted by each run's iteration count.
+    pub fn aggregate(mut runs: Vec<Stats>) -> Option<Stats> {
+        if runs.len() <= 1 {
+            return runs.pop();
+        }
+
+        let index_by = |key: fn(&Stats) -> FineDuration| -> Vec<usize> {
+            let mut indices: Vec<usize> = (0..runs.len()).collect();
+            indices.sort_by_key(|&i| key(&runs[i]));
+            indices
+        };
+
+        let fastest = index_by(|stats| stats.time.fastest)[0];
+        let slowest = *index_by(|stats| stats.time.slowest).last()?;
+        let median = index_by(|stats| stats.time.median)[runs.len() / 2];
+
+        let sample_count = runs.iter().map(|stats| stats.sample_count).sum();
+        let cpu_migrations = runs.iter().map(|stats| stats.cpu_migrations).sum();
+        let rejected_count = runs.iter().map(|stats| stats.rejected_count).sum();
+        let fastest_iter = runs.iter().filter_map(|stats| stats.fastest_iter).min();
+        let iter_count: u64 = runs.iter().map(|stats| stats.iter_count).sum();
+        let total_time =
+            runs.iter().fold(FineDuration::default(), |total, stats| total + stats.total_time);
+
+        // Weighted by each run's share of iterations, like means.
+        let child_cpu_time = runs
+            .iter()
+            .map(|stats| Some(stats.child_cpu_time?.picos * stats.iter_count as u128))
+            .sum::<Option<u128>>()
+            .map(|picos| FineDuration {
+                picos: picos.checked_div(iter_count as u128).unwrap_or_default(),
+            });
+
+        // Weights each run's mean by its share of iterations.
+        let weighted_mean = |mean: &dyn Fn(&Stats) -> f64| -> f64 {
+            let sum: f64 = runs.iter().map(|stats| mean(stats) * stats.iter_count as f64).sum();
+            if iter_count == 0 {
+                0.0
+            } else {
+                sum / iter_count as f64
+            }
+        };
+
+        let alloc_tallies = AllocOpMap {
+            values: AllocOp::ALL.map(|op| {
+                let combine = |select: fn(&AllocTally<StatsSet<f64>>) -> StatsSet<f64>| StatsSet {
+                    fastest: select(runs[fastest].alloc_tallies.get(op)).fastest,
+                    slowest: select(runs[slowest].alloc_tallies.get(op)).slowest,
+                    median: select(runs[median].alloc_tallies.get(op)).median,
+                    mean: weighted_mean(&|stats| select(stats.alloc_tallies.get(op)).mean),
+                };
+
+                AllocTally {
+                    count: combine(|tally| tally.count),
+                    size: combine(|tally| tally.size),
+                }
+            }),
+        };
+
+        let counts = KnownCounterKind::ALL.map(|counter_kind| {
+            let counts: Vec<&StatsSet<MaxCountUInt>> =
+                runs.iter().map(|stats| stats.get_counts(counter_kind)).collect::<Option<_>>()?;
+
+            Some(StatsSet {
+                fastest: counts[fastest].fastest,
+                slowest: counts[slowest].slowest,
+                median: counts[median].median,
+                mean: weighted_mean(&|stats| {
+                    stats.get_counts(counter_kind).map_or(0.0, |counts| counts.mean as f64)
+                })
+                .round() as MaxCountUInt,
+            })
+        });
+
+        Some(Stats {
+            sample_count,
+            iter_count,
+            time: StatsSet {
+                fastest: runs[fastest].time.fastest,
+                slowest: runs[slowest].time.slowest,
+                median: runs[median].time.median,
+                mean: FineDuration {
+                    picos: total_time.picos.checked_div(iter_count as u128).unwrap_or_default(),
+                },
+            },
+            total_time,
+            alloc_tallies,
+            counts,
+            run_variance: None,
+            cpu_migrations,
+            rejected_count,
+            fastest_iter,
+            child_cpu_time,
+        })
+    }
+}
+
+/// Returns the harmonic mean of rates, or `None` if there are no positive
+/// rates.
+///
+/// This is the rate of doing an equal amount of work at each rate, so unlike
+/// the arithmetic mean, a few fast rates do not overstate the overall rate.
+pub(crate) fn harmonic_mean(rates: &[f64]) -> Option<f64> {
+    let rates = rates.iter().filter(|rate| rate.is_finite() && **rate > 0.0);
+
+    let (count, reciprocal_sum) =
+        rates.fold((0usize, 0.0), |(count, sum), rate| (count + 1, sum + rate.recip()));
+
+    (count > 0).then(|| count as f64 / reciprocal_sum)
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub(crate) struct StatsSet<T> {
     /// Associated with minimum amount of time taken by an iteration.
     pub fastest: T,
@@ -55,3 +201,105 @@ impl StatsSet<f64> {
         self.fastest == 0.0 && self.slowest == 0.0 && self.median == 0.0 && self.mean == 0.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(iter_count: u64, [fastest, slowest, median]: [u128; 3], total_time: u128) -> Stats {
+        let set =
+            |value: f64| StatsSet { fastest: value, slowest: value, median: value, mean: value };
+
+        Stats {
+            sample_count: 1,
+            iter_count,
+            time: StatsSet {
+                fastest: FineDuration { picos: fastest },
+                slowest: FineDuration { picos: slowest },
+                median: FineDuration { picos: median },
+                mean: FineDuration { picos: total_time / iter_count as u128 },
+            },
+            total_time: FineDuration { picos: total_time },
+            alloc_tallies: AllocOpMap::from_fn(|_| AllocTally {
+                count: set(iter_count as f64),
+                size: set(0.0),
+            }),
+            counts: Default::default(),
+            run_variance: None,
+            cpu_migrations: Some(1),
+            rejected_count: 2,
+            fastest_iter: Some(FineDuration { picos: fastest }),
+            child_cpu_time: Some(FineDuration { picos: median }),
+        }
+    }
+
+    #[test]
+    fn harmonic_mean() {
+        assert_eq!(super::harmonic_mean(&[]), None);
+        assert_eq!(super::harmonic_mean(&[0.0, f64::INFINITY]), None);
+        assert_eq!(super::harmonic_mean(&[5.0]), Some(5.0));
+
+        // Doing 1 unit of work at each rate takes 1 + 0.5 + 0.25 seconds.
+        let mean = super::harmonic_mean(&[1.0, 2.0, 4.0]).unwrap();
+        assert!((mean - 3.0 / 1.75).abs() < 1e-9);
+    }
+
+    /// Migrations are only flagged if they happened in more than 5% of samples.
+    #[test]
+    fn many_cpu_migrations() {
+        let many = |sample_count: u32, cpu_migrations: Option<u64>| {
+            Stats { sample_count, cpu_migrations, ..stats(1, [1, 1, 1], 1) }.many_cpu_migrations()
+        };
+
+        assert_eq!(many(100, None), None);
+        assert_eq!(many(100, Some(0)), None);
+        assert_eq!(many(100, Some(5)), None);
+        assert_eq!(many(100, Some(6)), Some(6));
+        assert_eq!(many(10, Some(1)), Some(1));
+        assert_eq!(many(19, Some(1)), Some(1));
+        assert_eq!(many(20, Some(1)), None);
+
+        // Aggregated runs pool both migrations and samples.
+        let runs = (0..20).map(|_| stats(1, [1, 1, 1], 1)).collect();
+        assert_eq!(Stats::aggregate(runs).unwrap().many_cpu_migrations(), Some(20));
+        let runs = vec![
+            Stats { sample_count: 10, ..stats(1, [1, 1, 1], 1) },
+            Stats { sample_count: 10, cpu_migrations: Some(0), ..stats(1, [1, 1, 1], 1) },
+        ];
+        assert_eq!(Stats::aggregate(runs).unwrap().many_cpu_migrations(), None);
+    }
+
+    #[test]
+    fn aggregate() {
+        assert!(Stats::aggregate(Vec::new()).is_none());
+
+        let runs = vec![
+            stats(10, [5, 50, 20], 200),
+            stats(30, [3, 30, 10], 300),
+            stats(60, [4, 90, 30], 1500),
+        ];
+
+        let stats = Stats::aggregate(runs).unwrap();
+        assert_eq!(stats.sample_count, 3);
+        assert_eq!(stats.cpu_migrations, Some(3));
+        assert_eq!(stats.rejected_count, 6);
+        assert_eq!(stats.fastest_iter, Some(FineDuration { picos: 3 }));
+        // Child CPU time is a mean weighted by iterations: (200 + 300 + 1800) / 100.
+        assert_eq!(stats.child_cpu_time, Some(FineDuration { picos: 23 }));
+        assert_eq!(stats.iter_count, 100);
+        assert_eq!(stats.total_time.picos, 2000);
+
+        assert_eq!(stats.time.fastest.picos, 3);
+        assert_eq!(stats.time.slowest.picos, 90);
+        assert_eq!(stats.time.median.picos, 20);
+        assert_eq!(stats.time.mean.picos, 20);
+
+        // Allocations come from the same run as each time, and the mean is
+        // weighted by iterations.
+        let count = stats.alloc_tallies.get(AllocOp::Alloc).count;
+        assert_eq!(count.fastest, 30.0);
+        assert_eq!(count.slowest, 60.0);
+        assert_eq!(count.median, 10.0);
+        assert_eq!(count.mean, (10.0 * 10.0 + 30.0 * 30.0 + 60.0 * 60.0) / 100.0);
+    }
+}
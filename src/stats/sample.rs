@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
     alloc::ThreadAllocTallyMap,
@@ -6,6 +6,10 @@ use crate::{
     time::{FineDuration, Timer, Timestamp},
 };
 
+/// Samples this many times slower than the median are rejected as clock
+/// anomalies, such as from the system being suspended or the clock stepping.
+const ANOMALY_MEDIAN_MULTIPLE: u128 = 1_000;
+
 /// Timing measurement.
 pub(crate) struct TimeSample {
     /// The time this sample took to run.
@@ -13,6 +17,10 @@ pub(crate) struct TimeSample {
     /// This is gotten from [`RawSample`] with:
     /// `end.duration_since(start, timer).clamp_to(timer.precision())`.
     pub duration: FineDuration,
+
+    /// Whether the measured time is implausible and thus excluded from
+    /// statistics.
+    pub is_rejected: bool,
 }
 
 /// Unprocessed measurement.
@@ -65,6 +73,17 @@ pub(crate) struct SampleCollection {
     /// To associate this with samples in `all`, stride over `all` with the
     /// thread count.
     pub threads: Vec<ThreadSample>,
+
+    /// The number of times the benchmarking thread was observed on a different
+    /// CPU between samples, or `None` if the OS does not expose its CPU.
+    pub cpu_migrations: Option<u64>,
+
+    /// The fastest single iteration, if timed with the `fastest_iter` option.
+    pub fastest_iter: Option<FineDuration>,
+
+    /// The total CPU time of child processes over all samples, if counted with
+    /// the `child_cpu_time` option.
+    pub child_cpu_time: Option<Duration>,
 }
 
 impl SampleCollection {
@@ -74,27 +93,121 @@ impl SampleCollection {
         self.time_samples.clear();
         self.alloc_tallies.clear();
         self.threads.clear();
+
+        if let Some(cpu_migrations) = &mut self.cpu_migrations {
+            *cpu_migrations = 0;
+        }
+
+        self.fastest_iter = None;
+
+        if let Some(child_cpu_time) = &mut self.child_cpu_time {
+            *child_cpu_time = Duration::ZERO;
+        }
+    }
+
+    /// Returns samples that were not rejected.
+    #[inline]
+    pub fn accepted_samples(&self) -> impl Iterator<Item = &TimeSample> {
+        self.time_samples.iter().filter(|s| !s.is_rejected)
+    }
+
+    /// Returns the number of samples that were not rejected.
+    #[inline]
+    pub fn accepted_count(&self) -> usize {
+        self.accepted_samples().count()
     }
 
-    /// Computes the total number of iterations across all samples.
+    /// Returns the number of samples rejected as clock anomalies.
+    #[inline]
+    pub fn rejected_count(&self) -> usize {
+        self.time_samples.len() - self.accepted_count()
+    }
+
+    /// Computes the total number of iterations across accepted samples.
     ///
     /// We use `u64` in case sample count and sizes are huge.
     #[inline]
     pub fn iter_count(&self) -> u64 {
-        self.sample_size as u64 * self.time_samples.len() as u64
+        self.sample_size as u64 * self.accepted_count() as u64
     }
 
-    /// Computes the total time across all samples.
+    /// Computes the total time across accepted samples.
     #[inline]
     pub fn total_duration(&self) -> FineDuration {
-        FineDuration { picos: self.time_samples.iter().map(|s| s.duration.picos).sum() }
+        FineDuration { picos: self.accepted_samples().map(|s| s.duration.picos).sum() }
     }
 
-    /// Returns all samples sorted by duration.
+    /// Returns the per-iteration duration of each accepted sample.
+    #[inline]
+    pub fn iter_durations(&self) -> Vec<FineDuration> {
+        self.accepted_samples().map(|s| s.duration / self.sample_size).collect()
+    }
+
+    /// Returns accepted samples sorted by duration.
     #[inline]
     pub fn sorted_samples(&self) -> Vec<&TimeSample> {
-        let mut result: Vec<&TimeSample> = self.time_samples.iter().collect();
+        let mut result: Vec<&TimeSample> = self.accepted_samples().collect();
         result.sort_unstable_by_key(|s| s.duration);
         result
     }
+
+    /// Rejects samples that are wildly slower than the median, which happens
+    /// when the system is suspended or the clock steps forward mid-sample.
+    pub fn reject_anomalies(&mut self) {
+        let sorted_samples = self.sorted_samples();
+
+        let Some(median) = sorted_samples.get(sorted_samples.len() / 2) else {
+            return;
+        };
+
+        let max_picos = median.duration.picos.saturating_mul(ANOMALY_MEDIAN_MULTIPLE);
+        if max_picos == 0 {
+            return;
+        }
+
+        for sample in &mut self.time_samples {
+            if sample.duration.picos > max_picos {
+                sample.is_rejected = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collection(picos: &[u128]) -> SampleCollection {
+        SampleCollection {
+            sample_size: 1,
+            time_samples: picos
+                .iter()
+                .map(|&picos| TimeSample { duration: FineDuration { picos }, is_rejected: false })
+                .collect(),
+            ..SampleCollection::default()
+        }
+    }
+
+    #[test]
+    fn reject_anomalies() {
+        let mut samples = collection(&[10, 12, 11, 5_000_000, 9]);
+        samples.reject_anomalies();
+
+        assert_eq!(samples.rejected_count(), 1);
+        assert_eq!(samples.iter_count(), 4);
+        assert_eq!(samples.total_duration().picos, 42);
+        assert_eq!(samples.sorted_samples().last().map(|s| s.duration.picos), Some(12),);
+    }
+
+    #[test]
+    fn keep_plausible() {
+        let mut samples = collection(&[10, 20, 9_000]);
+        samples.reject_anomalies();
+        assert_eq!(samples.rejected_count(), 0);
+
+        // A zero median cannot bound other samples.
+        let mut samples = collection(&[0, 0, 7]);
+        samples.reject_anomalies();
+        assert_eq!(samples.rejected_count(), 0);
+    }
 }
@@ -0,0 +1,164 @@
+//! Variance of sample times within and across repeated runs.
+
+use crate::time::FineDuration;
+
+/// How much of the variance in per-iteration sample times comes from
+/// differences between runs versus differences within each run.
+///
+/// A large between-run share suggests environmental noise, such as CPU
+/// frequency scaling or other processes, whereas a large within-run share
+/// suggests variance inherent to the benchmarked code.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RunVariance {
+    /// Share of the total sum of squares explained by run means, in `0..=1`.
+    pub between: f64,
+
+    /// Share of the total sum of squares within runs, in `0..=1`.
+    pub within: f64,
+}
+
+impl RunVariance {
+    /// Decomposes the variance of per-iteration sample times grouped by run,
+    /// or returns `None` if there are fewer than 2 non-empty runs.
+    pub fn new(runs: &[Vec<FineDuration>]) -> Option<Self> {
+        let picos = |sample: &FineDuration| sample.picos as f64;
+
+        let runs: Vec<&[FineDuration]> =
+            runs.iter().map(Vec::as_slice).filter(|run| !run.is_empty()).collect();
+
+        if runs.len() < 2 {
+            return None;
+        }
+
+        let total_len: usize = runs.iter().map(|run| run.len()).sum();
+        let grand_mean =
+            runs.iter().flat_map(|run| run.iter()).map(picos).sum::<f64>() / total_len as f64;
+
+        let mut between_squares = 0.0;
+        let mut within_squares = 0.0;
+
+        for run in &runs {
+            let run_mean = run.iter().map(picos).sum::<f64>() / run.len() as f64;
+
+            between_squares += run.len() as f64 * (run_mean - grand_mean).powi(2);
+            within_squares +=
+                run.iter().map(|sample| (picos(sample) - run_mean).powi(2)).sum::<f64>();
+        }
+
+        let total_squares = between_squares + within_squares;
+
+        if total_squares == 0.0 {
+            // All samples are equal, so there is no variance to attribute.
+            return Some(Self { between: 0.0, within: 0.0 });
+        }
+
+        Some(Self {
+            between: between_squares / total_squares,
+            within: within_squares / total_squares,
+        })
+    }
+}
+
+/// Mean and variance of values that are added one at a time, using Welford's
+/// algorithm.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct RunningVariance {
+    count: u64,
+    mean: f64,
+
+    /// Sum of squared differences from the mean.
+    squares: f64,
+}
+
+impl RunningVariance {
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn push(&mut self, value: f64) {
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    /// Returns the standard error of the mean as a fraction of the mean, or
+    /// `None` if there are fewer than 2 values or the mean is not positive.
+    pub fn relative_standard_error(&self) -> Option<f64> {
+        if self.count < 2 || self.mean <= 0.0 {
+            return None;
+        }
+
+        let count = self.count as f64;
+        let variance = self.squares / (count - 1.0);
+
+        Some((variance / count).sqrt() / self.mean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn durations(picos: impl IntoIterator<Item = u128>) -> Vec<FineDuration> {
+        picos.into_iter().map(|picos| FineDuration { picos }).collect()
+    }
+
+    #[test]
+    fn between_runs() {
+        let variance =
+            RunVariance::new(&[durations([10, 10, 10]), durations([20, 20, 20])]).unwrap();
+
+        assert_eq!(variance.between, 1.0);
+        assert_eq!(variance.within, 0.0);
+    }
+
+    #[test]
+    fn within_runs() {
+        let variance = RunVariance::new(&[durations([10, 20]), durations([20, 10])]).unwrap();
+
+        assert_eq!(variance.between, 0.0);
+        assert_eq!(variance.within, 1.0);
+    }
+
+    #[test]
+    fn mixed() {
+        // Run means are 15 and 25, and the grand mean is 20:
+        // - Between: 2 * 5² + 2 * 5² = 100
+        // - Within: 4 * 5² = 100
+        let variance = RunVariance::new(&[durations([10, 20]), durations([20, 30])]).unwrap();
+
+        assert!((variance.between - 0.5).abs() < 1e-9);
+        assert!((variance.within - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn running_variance() {
+        let mut variance = RunningVariance::default();
+        assert_eq!(variance.relative_standard_error(), None);
+
+        variance.push(10.0);
+        assert_eq!(variance.relative_standard_error(), None);
+
+        // The sample standard deviation of 10, 20, 30 is 10, so the standard
+        // error is 10 / √3 and the mean is 20.
+        variance.push(20.0);
+        variance.push(30.0);
+        let error = variance.relative_standard_error().unwrap();
+        assert!((error - 10.0 / 3f64.sqrt() / 20.0).abs() < 1e-9);
+
+        let mut variance = RunningVariance::default();
+        variance.push(5.0);
+        variance.push(5.0);
+        assert_eq!(variance.relative_standard_error(), Some(0.0));
+    }
+
+    #[test]
+    fn too_few_runs() {
+        assert!(RunVariance::new(&[]).is_none());
+        assert!(RunVariance::new(&[durations([1, 2])]).is_none());
+        assert!(RunVariance::new(&[durations([1, 2]), Vec::new()]).is_none());
+    }
+}
@@ -0,0 +1,241 @@
+//! Helpers for generating [`args`](macro@crate::bench#args) sweeps.
+
+use std::ops::{Bound, RangeBounds};
+
+/// Primitive integers usable by [`powers_of_two`] and [`linear`].
+///
+/// This trait is sealed and cannot be implemented outside of Divan.
+pub trait SweepInt: Copy + PartialOrd + private::Sealed {
+    #[doc(hidden)]
+    const ZERO: Self;
+
+    #[doc(hidden)]
+    const MIN: Self;
+
+    #[doc(hidden)]
+    const MAX: Self;
+
+    /// The number of bits that can hold a power of two.
+    #[doc(hidden)]
+    const POW2_BITS: u32;
+
+    #[doc(hidden)]
+    fn to_u32(self) -> Option<u32>;
+
+    #[doc(hidden)]
+    fn pow2(exp: u32) -> Self;
+
+    #[doc(hidden)]
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    #[doc(hidden)]
+    fn checked_inc(self) -> Option<Self>;
+
+    #[doc(hidden)]
+    fn checked_dec(self) -> Option<Self>;
+}
+
+macro_rules! impl_sweep_int {
+    ($($i:ty),+) => {
+        $(impl private::Sealed for $i {}
+
+        impl SweepInt for $i {
+            const ZERO: Self = 0;
+            const MIN: Self = <$i>::MIN;
+            const MAX: Self = <$i>::MAX;
+            const POW2_BITS: u32 = <$i>::BITS - (<$i>::MIN != 0) as u32;
+
+            #[inline]
+            fn to_u32(self) -> Option<u32> {
+                self.try_into().ok()
+            }
+
+            #[inline]
+            fn pow2(exp: u32) -> Self {
+                1 << exp
+            }
+
+            #[inline]
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$i>::checked_add(self, rhs)
+            }
+
+            #[inline]
+            fn checked_inc(self) -> Option<Self> {
+                <$i>::checked_add(self, 1)
+            }
+
+            #[inline]
+            fn checked_dec(self) -> Option<Self> {
+                <$i>::checked_sub(self, 1)
+            }
+        })+
+    };
+}
+
+impl_sweep_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Returns an iterator over powers of two for each exponent in `exponents`.
+///
+/// This is intended for declaring size sweeps in the
+/// [`args`](macro@crate::bench#args) option. Exponents have the same type as
+/// the resulting values, so that the integer type is inferred from the
+/// benchmarked function's argument.
+///
+/// # Examples
+///
+/// ```
+/// // Benchmarks 2, 4, 8, ..., 1048576.
+/// #[divan::bench(args = divan::powers_of_two(1..=20))]
+/// fn fill_vec(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if an exponent is negative or if any power of two is not
+/// representable by `T`. An unbounded end stops at the largest representable
+/// power of two.
+#[track_caller]
+pub fn powers_of_two<T: SweepInt>(
+    exponents: impl RangeBounds<T>,
+) -> impl Iterator<Item = T> + Clone {
+    #[track_caller]
+    fn exp<T: SweepInt>(exp: &T) -> u32 {
+        assert!(*exp >= T::ZERO, "sweep exponent must not be negative");
+        exp.to_u32().unwrap_or(u32::MAX)
+    }
+
+    let start = match exponents.start_bound() {
+        Bound::Included(start) => exp(start),
+        Bound::Excluded(start) => exp(start).saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+
+    // Exclusive end.
+    let end = match exponents.end_bound() {
+        Bound::Included(end) => exp(end).saturating_add(1),
+        Bound::Excluded(end) => exp(end),
+        Bound::Unbounded => T::POW2_BITS,
+    };
+
+    assert!(
+        start >= end || end <= T::POW2_BITS,
+        "2^{} is too large for the argument type",
+        end - 1,
+    );
+
+    (start..end).map(T::pow2)
+}
+
+/// Returns an iterator over values in `range`, incrementing by `step`.
+///
+/// This is intended for declaring evenly-spaced sweeps in the
+/// [`args`](macro@crate::bench#args) option. The integer type is inferred from
+/// the benchmarked function's argument.
+///
+/// # Examples
+///
+/// ```
+/// // Benchmarks 0, 100, 200, ..., 900.
+/// #[divan::bench(args = divan::linear(0..1000, 100))]
+/// fn fill_vec(len: usize) -> Vec<u8> {
+///     vec![0; len]
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if `step` is not positive.
+#[track_caller]
+pub fn linear<T: SweepInt>(range: impl RangeBounds<T>, step: T) -> impl Iterator<Item = T> + Clone {
+    assert!(step > T::ZERO, "sweep step must be positive");
+
+    let start = match range.start_bound() {
+        Bound::Included(&start) => Some(start),
+        Bound::Excluded(&start) => start.checked_inc(),
+        Bound::Unbounded => Some(T::MIN),
+    };
+
+    // Inclusive end.
+    let last = match range.end_bound() {
+        Bound::Included(&end) => Some(end),
+        Bound::Excluded(&end) => end.checked_dec(),
+        Bound::Unbounded => Some(T::MAX),
+    };
+
+    let (mut next, last) = match (start, last) {
+        (Some(start), Some(last)) if start <= last => (Some(start), last),
+        _ => (None, T::MIN),
+    };
+
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = current.checked_add(step).filter(|&next| next <= last);
+        Some(current)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn powers_of_two() {
+        #[track_caller]
+        fn test(exponents: impl RangeBounds<u32>, expected: &[u32]) {
+            let values: Vec<u32> = super::powers_of_two(exponents).collect();
+            assert_eq!(values, expected);
+        }
+
+        test(0..=4, &[1, 2, 4, 8, 16]);
+        test(1..4, &[2, 4, 8]);
+        test(3..3, &[]);
+        test(31.., &[1 << 31]);
+
+        assert_eq!(super::powers_of_two::<i8>(..).last(), Some(64));
+        assert_eq!(super::powers_of_two::<u128>(..).count(), 128);
+        assert_eq!(super::powers_of_two(2u64..=3).collect::<Vec<_>>(), [4, 8]);
+    }
+
+    #[test]
+    #[should_panic = "2^8 is too large"]
+    fn powers_of_two_overflow() {
+        _ = super::powers_of_two::<u8>(0..=8);
+    }
+
+    #[test]
+    #[should_panic = "sweep exponent must not be negative"]
+    fn powers_of_two_negative() {
+        _ = super::powers_of_two(-1..=2);
+    }
+
+    #[test]
+    fn linear() {
+        #[track_caller]
+        fn test(range: impl RangeBounds<i32>, step: i32, expected: &[i32]) {
+            let values: Vec<i32> = super::linear(range, step).collect();
+            assert_eq!(values, expected);
+        }
+
+        test(0..10, 3, &[0, 3, 6, 9]);
+        test(0..=9, 3, &[0, 3, 6, 9]);
+        test(0..9, 3, &[0, 3, 6]);
+        test(-4..=4, 4, &[-4, 0, 4]);
+        test(5..5, 1, &[]);
+        test(i32::MAX - 1.., 1, &[i32::MAX - 1, i32::MAX]);
+
+        assert_eq!(super::linear(250u8.., 10).collect::<Vec<_>>(), [250]);
+    }
+
+    #[test]
+    #[should_panic = "sweep step must be positive"]
+    fn linear_zero_step() {
+        _ = super::linear(0..10, 0);
+    }
+}
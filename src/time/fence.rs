@@ -14,6 +14,55 @@ pub fn compiler_fence() {
     atomic::compiler_fence(atomic::Ordering::SeqCst);
 }
 
+/// Waits for all previous instructions to complete before executing later
+/// instructions.
+///
+/// Unlike [`full_fence`], which only orders memory operations, this prevents
+/// out-of-order execution from moving any work across the fence. It uses
+/// `cpuid` on x86 and `isb` on AArch64, and falls back to [`full_fence`] on
+/// other platforms.
+#[inline(always)]
+pub fn serializing_fence() {
+    // Miri does not support inline assembly.
+    if cfg!(miri) {
+        full_fence();
+        return;
+    }
+
+    #[cfg(all(
+        not(target_env = "sgx"),
+        any(target_arch = "x86_64", all(target_arch = "x86", target_feature = "sse")),
+    ))]
+    {
+        #[cfg(target_arch = "x86")]
+        use std::arch::x86;
+
+        #[cfg(target_arch = "x86_64")]
+        use std::arch::x86_64 as x86;
+
+        // SAFETY: `cpuid` is available on all targets matched above.
+        unsafe { x86::__cpuid(0) };
+
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    // SAFETY: An instruction synchronization barrier is memory safe.
+    unsafe {
+        // Leave off `nomem` because this should be a compiler fence.
+        std::arch::asm!("isb", options(nostack, preserves_flags));
+    }
+
+    #[cfg(not(any(
+        all(
+            not(target_env = "sgx"),
+            any(target_arch = "x86_64", all(target_arch = "x86", target_feature = "sse")),
+        ),
+        target_arch = "aarch64",
+    )))]
+    full_fence();
+}
+
 /// Stronger compiler fence on [platforms with stable `asm!`](https://doc.rust-lang.org/nightly/reference/inline-assembly.html).
 ///
 /// This prevents LLVM from removing loops or hoisting logic out of the
@@ -1,4 +1,4 @@
-use std::{fmt, ops, time::Duration};
+use std::{error::Error, fmt, ops, str::FromStr, time::Duration};
 
 use crate::util;
 
@@ -21,9 +21,92 @@ impl From<Duration> for FineDuration {
     }
 }
 
+impl TryFrom<FineDuration> for Duration {
+    type Error = Box<dyn Error + Send + Sync>;
+
+    #[inline]
+    fn try_from(duration: FineDuration) -> Result<Self, Self::Error> {
+        let secs = u64::try_from(duration.picos / picos::SEC)
+            .map_err(|_| format!("{duration} is too large to fit in `Duration`"))?;
+        let nanos = ((duration.picos % picos::SEC) / picos::NANOS) as u32;
+
+        Ok(Duration::new(secs, nanos))
+    }
+}
+
+/// Parses human-readable durations like "500us", "2.5ms", or "1m30s".
+///
+/// Each component is a decimal number followed by a unit of `ps`, `ns`,
+/// `us`/`µs`, `ms`, `s`, `m`, `h`, or `d`. Fractional parts are parsed exactly
+/// and truncated to picoseconds.
+impl FromStr for FineDuration {
+    type Err = Box<dyn Error + Send + Sync>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid duration '{s}'");
+        let overflow = || format!("duration '{s}' is too large");
+
+        if s.is_empty() {
+            return Err(invalid().into());
+        }
+
+        let mut total: u128 = 0;
+        let mut rest = s;
+
+        while !rest.is_empty() {
+            let number_len =
+                rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+            let (number, after_number) = rest.split_at(number_len);
+
+            let unit_len =
+                after_number.find(|c: char| c.is_ascii_digit()).unwrap_or(after_number.len());
+            let (unit, after_unit) = after_number.split_at(unit_len);
+            rest = after_unit;
+
+            let scale = match unit {
+                "ps" => TimeScale::PicoSec,
+                "ns" => TimeScale::NanoSec,
+                "us" | "µs" => TimeScale::MicroSec,
+                "ms" => TimeScale::MilliSec,
+                "s" => TimeScale::Sec,
+                "m" => TimeScale::Min,
+                "h" => TimeScale::Hour,
+                "d" => TimeScale::Day,
+                _ => return Err(invalid().into()),
+            }
+            .picos();
+
+            let (int, frac) = number.split_once('.').unwrap_or((number, ""));
+            if (int.is_empty() && frac.is_empty()) || frac.contains('.') {
+                return Err(invalid().into());
+            }
+
+            let mut picos = match int {
+                "" => 0,
+                int => u128::from_str(int)
+                    .ok()
+                    .and_then(|int| int.checked_mul(scale))
+                    .ok_or_else(overflow)?,
+            };
+
+            // Accumulate fractional digits until they no longer affect the
+            // number of picoseconds.
+            let mut place = scale;
+            for digit in frac.bytes() {
+                place /= 10;
+                picos += (digit - b'0') as u128 * place;
+            }
+
+            total = total.checked_add(picos).ok_or_else(overflow)?;
+        }
+
+        Ok(Self { picos: total })
+    }
+}
+
 impl fmt::Display for FineDuration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let sig_figs = f.precision().unwrap_or(4);
+        let sig_figs = f.precision().unwrap_or(util::fmt::DEFAULT_SIG_FIGS);
 
         let picos = self.picos;
         let mut scale = TimeScale::from_picos(picos);
@@ -228,6 +311,48 @@ impl TimeScale {
 mod tests {
     use super::*;
 
+    #[test]
+    fn from_str() {
+        #[track_caller]
+        fn test(s: &str, expected: u128) {
+            assert_eq!(s.parse::<FineDuration>().unwrap(), FineDuration { picos: expected });
+        }
+
+        test("0s", 0);
+        test("1ps", 1);
+        test("500us", 500 * picos::MICROS);
+        test("500µs", 500 * picos::MICROS);
+        test("2.5ms", 2_500 * picos::MICROS);
+        test(".5s", 500 * picos::MILLIS);
+        test("3.s", 3 * picos::SEC);
+        test("1m30s", 90 * picos::SEC);
+        test("1h1m1s1ms", picos::HOUR + picos::MIN + picos::SEC + picos::MILLIS);
+        test("1d", picos::DAY);
+        test("0.0000001ns", 0);
+        test("1.0000001ns", picos::NANOS);
+
+        for invalid in ["", "1", "s", ".s", "1.2.3s", "1 s", "-1s", "1sec", "1s2"] {
+            assert!(invalid.parse::<FineDuration>().is_err(), "{invalid:?} parsed");
+        }
+
+        assert!(format!("{}s", u128::MAX).parse::<FineDuration>().is_err());
+    }
+
+    #[test]
+    fn into_duration() {
+        #[track_caller]
+        fn test(picos: u128, expected: Duration) {
+            assert_eq!(Duration::try_from(FineDuration { picos }).unwrap(), expected);
+        }
+
+        test(0, Duration::ZERO);
+        test(999, Duration::ZERO);
+        test(1_500 * picos::MILLIS, Duration::from_millis(1_500));
+        test(picos::DAY + 1_000, Duration::from_secs(86_400) + Duration::from_nanos(1));
+
+        assert!(Duration::try_from(FineDuration::MAX).is_err());
+    }
+
     #[test]
     fn clamp_to() {
         #[track_caller]
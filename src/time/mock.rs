@@ -0,0 +1,105 @@
+use std::sync::{Mutex, PoisonError};
+#[cfg(any(test, feature = "mock_clock"))]
+use std::time::Duration;
+
+use crate::time::FineDuration;
+
+/// The virtual time in picoseconds.
+///
+/// This is a `Mutex` rather than `AtomicU64` so that Divan still builds for
+/// targets without 64-bit atomics.
+static NOW_PICOS: Mutex<u64> = Mutex::new(0);
+
+#[inline]
+fn now_picos() -> std::sync::MutexGuard<'static, u64> {
+    NOW_PICOS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A deterministic virtual clock for testing code built on Divan.
+///
+/// This requires the `mock_clock` crate feature, which is meant to be enabled
+/// only for tests, such as through `[dev-dependencies]`.
+///
+/// When enabled with [`Divan::mock_clock`](crate::Divan::mock_clock), samples
+/// are timed by this clock rather than by real time. It only moves when
+/// [`advance`](Self::advance) is called, so benchmarks can simulate how long
+/// each iteration takes. This makes reported statistics, and behavior depending
+/// on [`min_time`](macro@crate::bench#min_time) and
+/// [`max_time`](macro@crate::bench#max_time), reproducible.
+///
+/// The clock is shared by all threads, so tests that advance it should not run
+/// concurrently with other tests that use it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use divan::MockClock;
+///
+/// #[divan::bench(sample_count = 10, sample_size = 100)]
+/// fn simulated() {
+///     // Every iteration takes exactly 10 virtual nanoseconds.
+///     MockClock::advance(Duration::from_nanos(10));
+/// }
+///
+/// fn main() {
+///     divan::Divan::from_args().mock_clock().main();
+/// }
+/// ```
+#[cfg(any(test, feature = "mock_clock"))]
+#[derive(Debug)]
+pub struct MockClock {
+    _private: (),
+}
+
+#[cfg(any(test, feature = "mock_clock"))]
+impl MockClock {
+    /// Moves the virtual time forward by `duration`.
+    #[inline]
+    pub fn advance(duration: Duration) {
+        let picos = duration.as_nanos().saturating_mul(1_000);
+        let picos = u64::try_from(picos).unwrap_or(u64::MAX);
+
+        let mut now = now_picos();
+        *now = now.saturating_add(picos);
+    }
+
+    /// Returns the virtual time since the start of the program.
+    #[inline]
+    pub fn now() -> Duration {
+        let picos = *now_picos();
+        Duration::from_nanos(picos / 1_000)
+    }
+}
+
+/// A reading of the virtual time.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub(crate) struct MockTimestamp {
+    pub picos: u64,
+}
+
+impl MockTimestamp {
+    #[inline(always)]
+    pub fn now() -> Self {
+        Self { picos: *now_picos() }
+    }
+
+    #[inline]
+    pub fn duration_since(self, earlier: Self) -> FineDuration {
+        FineDuration { picos: self.picos.saturating_sub(earlier.picos) as u128 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        // Other tests may advance the shared clock concurrently.
+        let before = MockClock::now();
+        MockClock::advance(Duration::from_micros(5));
+        assert!(MockClock::now() >= before + Duration::from_micros(5));
+    }
+}
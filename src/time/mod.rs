@@ -3,10 +3,14 @@ use std::time::Duration;
 pub mod fence;
 
 mod fine_duration;
+mod mock;
 mod timer;
 mod timestamp;
 
 pub(crate) use fine_duration::*;
+#[cfg(any(test, feature = "mock_clock"))]
+pub use mock::MockClock;
+pub(crate) use mock::MockTimestamp;
 pub(crate) use timer::*;
 pub(crate) use timestamp::*;
 
@@ -36,3 +40,13 @@ impl IntoDuration for f64 {
         Duration::from_secs_f64(self)
     }
 }
+
+impl IntoDuration for &str {
+    #[track_caller]
+    fn into_duration(self) -> Duration {
+        match self.parse::<FineDuration>().and_then(Duration::try_from) {
+            Ok(duration) => duration,
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
@@ -14,10 +14,13 @@ pub(crate) enum Timer {
         /// [`TscTimestamp::frequency`].
         frequency: NonZeroU64,
     },
+
+    /// [`MockClock`](crate::MockClock) virtual time.
+    Mock,
 }
 
 impl Timer {
-    const COUNT: usize = 2;
+    const COUNT: usize = 3;
 
     /// Returns all available timers.
     #[cfg(test)]
@@ -37,11 +40,22 @@ impl Timer {
         Ok(Self::Tsc { frequency: TscTimestamp::frequency()? })
     }
 
+    /// Returns the CPU timestamp counter frequency if this is
+    /// [`Timer::Tsc`].
+    #[inline]
+    pub fn tsc_frequency(self) -> Option<NonZeroU64> {
+        match self {
+            Self::Os | Self::Mock => None,
+            Self::Tsc { frequency } => Some(frequency),
+        }
+    }
+
     #[inline]
     pub fn kind(self) -> TimerKind {
         match self {
             Self::Os => TimerKind::Os,
             Self::Tsc { .. } => TimerKind::Tsc,
+            Self::Mock => TimerKind::Mock,
         }
     }
 
@@ -49,7 +63,14 @@ impl Timer {
     ///
     /// The result is cached.
     pub fn precision(self) -> FineDuration {
-        static CACHED: [OnceLock<FineDuration>; Timer::COUNT] = [OnceLock::new(), OnceLock::new()];
+        static CACHED: [OnceLock<FineDuration>; Timer::COUNT] =
+            [OnceLock::new(), OnceLock::new(), OnceLock::new()];
+
+        // Virtual time has no resolution to measure, since it only moves when
+        // advanced. This matches the resolution of `Duration`.
+        if let Self::Mock = self {
+            return FineDuration { picos: 1_000 };
+        }
 
         let cached = &CACHED[self.kind() as usize];
 
@@ -76,15 +97,15 @@ impl Timer {
 
                 if delay_len == 0 {
                     // Immediate succession.
-                    sample_start = UntaggedTimestamp::start(timer_kind);
-                    sample_end = UntaggedTimestamp::end(timer_kind);
+                    sample_start = UntaggedTimestamp::start(timer_kind, false);
+                    sample_end = UntaggedTimestamp::end(timer_kind, false);
                 } else {
                     // Add delay.
-                    sample_start = UntaggedTimestamp::start(timer_kind);
+                    sample_start = UntaggedTimestamp::start(timer_kind, false);
                     for n in 0..delay_len {
                         crate::black_box(n);
                     }
-                    sample_end = UntaggedTimestamp::end(timer_kind);
+                    sample_end = UntaggedTimestamp::end(timer_kind, false);
                 }
 
                 // SAFETY: These values are guaranteed to be the correct variant
@@ -139,13 +160,13 @@ impl Timer {
         let mut min_sample = FineDuration::default();
 
         for _ in 0..sample_count {
-            let start = UntaggedTimestamp::start(timer_kind);
+            let start = UntaggedTimestamp::start(timer_kind, false);
 
             for i in 0..sample_size {
                 _ = crate::black_box(i);
             }
 
-            let end = UntaggedTimestamp::end(timer_kind);
+            let end = UntaggedTimestamp::end(timer_kind, false);
 
             // SAFETY: These values are guaranteed to be the correct variant because
             // they were created from the same `timer_kind`.
@@ -171,6 +192,9 @@ pub(crate) enum TimerKind {
 
     /// CPU timestamp counter.
     Tsc,
+
+    /// [`MockClock`](crate::MockClock) virtual time.
+    Mock,
 }
 
 #[cfg(feature = "internal_benches")]
@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use crate::time::{fence, FineDuration, Timer, TimerKind};
+use crate::time::{fence, FineDuration, MockTimestamp, Timer, TimerKind};
 
 mod tsc;
 
@@ -14,6 +14,9 @@ pub(crate) enum Timestamp {
 
     /// [CPU timestamp counter](https://en.wikipedia.org/wiki/Time_Stamp_Counter).
     Tsc(TscTimestamp),
+
+    /// [`MockClock`](crate::MockClock) time.
+    Mock(MockTimestamp),
 }
 
 impl Timestamp {
@@ -23,6 +26,7 @@ impl Timestamp {
         let value = match timer_kind {
             TimerKind::Os => Self::Os(Instant::now()),
             TimerKind::Tsc => Self::Tsc(TscTimestamp::start()),
+            TimerKind::Mock => Self::Mock(MockTimestamp::now()),
         };
         fence::compiler_fence();
         value
@@ -34,6 +38,7 @@ impl Timestamp {
             (Self::Tsc(this), Self::Tsc(earlier), Timer::Tsc { frequency }) => {
                 this.duration_since(earlier, frequency)
             }
+            (Self::Mock(this), Self::Mock(earlier), Timer::Mock) => this.duration_since(earlier),
             _ => unreachable!(),
         }
     }
@@ -53,28 +58,57 @@ pub(crate) union UntaggedTimestamp {
 
     /// [`Timestamp::Tsc`].
     pub tsc: TscTimestamp,
+
+    /// [`Timestamp::Mock`].
+    pub mock: MockTimestamp,
 }
 
 impl UntaggedTimestamp {
+    /// Reads the timestamp at the start of a sample.
+    ///
+    /// If `serialize` is set, then [`fence::serializing_fence`] is used to
+    /// ensure previous work completes before the timestamp is read.
     #[inline(always)]
-    pub fn start(timer_kind: TimerKind) -> Self {
-        fence::full_fence();
+    pub fn start(timer_kind: TimerKind, serialize: bool) -> Self {
+        if serialize {
+            fence::serializing_fence();
+        } else {
+            fence::full_fence();
+        }
         let value = match timer_kind {
             TimerKind::Os => Self { os: Instant::now() },
             TimerKind::Tsc => Self { tsc: TscTimestamp::start() },
+            TimerKind::Mock => Self { mock: MockTimestamp::now() },
         };
         fence::compiler_fence();
         value
     }
 
+    /// Reads the timestamp at the end of a sample.
+    ///
+    /// If `serialize` is set, then [`fence::serializing_fence`] is used to
+    /// ensure the timestamp is read before subsequent work starts. Serializing
+    /// instructions are kept out of the measured section where possible, since
+    /// they can be very slow under virtualization.
     #[inline(always)]
-    pub fn end(timer_kind: TimerKind) -> Self {
+    pub fn end(timer_kind: TimerKind, serialize: bool) -> Self {
+        // On x86, the timestamp read already waits for previous instructions.
+        // On AArch64, `cntvct_el0` may be read early without a barrier.
+        #[cfg(target_arch = "aarch64")]
+        if serialize {
+            fence::serializing_fence();
+        }
         fence::compiler_fence();
         let value = match timer_kind {
             TimerKind::Os => Self { os: Instant::now() },
             TimerKind::Tsc => Self { tsc: TscTimestamp::end() },
+            TimerKind::Mock => Self { mock: MockTimestamp::now() },
         };
-        fence::full_fence();
+        if serialize {
+            fence::serializing_fence();
+        } else {
+            fence::full_fence();
+        }
         value
     }
 
@@ -83,6 +117,7 @@ impl UntaggedTimestamp {
         match timer_kind {
             TimerKind::Os => Timestamp::Os(self.os),
             TimerKind::Tsc => Timestamp::Tsc(self.tsc),
+            TimerKind::Mock => Timestamp::Mock(self.mock),
         }
     }
 }
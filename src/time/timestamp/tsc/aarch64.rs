@@ -2,13 +2,28 @@ use std::arch::asm;
 
 use crate::time::TscUnavailable;
 
+/// Returns the frequency of `cntvct_el0`.
+///
+/// The nominal frequency in `cntfrq_el0` is set by firmware and some systems
+/// misreport it, so it is only used if within 0.1% of the measured frequency.
+pub(crate) fn frequency() -> Result<u64, TscUnavailable> {
+    let nominal = nominal_frequency() as f64;
+    let measured = super::measure::measure_frequency(timestamp);
+
+    if measured * 0.999 < nominal && nominal < measured * 1.001 {
+        return Ok(nominal as u64);
+    }
+
+    Ok(measured.round() as u64)
+}
+
 /// Reads the [`cntfrq_el0`](https://developer.arm.com/documentation/ddi0595/2021-12/AArch64-Registers/CNTFRQ-EL0--Counter-timer-Frequency-register?lang=en)
 /// register.
 ///
 /// This value is set on system initialization and thus does not change between
 /// reads.
 #[inline]
-pub(crate) fn frequency() -> Result<u64, TscUnavailable> {
+fn nominal_frequency() -> u64 {
     unsafe {
         let frequency: u64;
         asm!(
@@ -16,7 +31,7 @@ pub(crate) fn frequency() -> Result<u64, TscUnavailable> {
             out(reg) frequency,
             options(nomem, nostack, preserves_flags, pure),
         );
-        Ok(frequency)
+        frequency
     }
 }
 
@@ -0,0 +1,86 @@
+//! Measures the frequency of a timestamp counter against [`Instant`].
+
+use std::time::{Duration, Instant};
+
+/// Returns the counter frequency by measuring it, where `read` reads the
+/// counter.
+pub(crate) fn measure_frequency(read: fn() -> u64) -> f64 {
+    const TRIES: usize = 8;
+
+    // Start with delay of 1ms up to 256ms (2^TRIES).
+    let mut delay_ms = 1;
+
+    let mut prev_measure = f64::NEG_INFINITY;
+    let mut measures = [0.0; TRIES];
+
+    for slot in &mut measures {
+        let measure = measure_frequency_once(read, Duration::from_millis(delay_ms));
+
+        // This measurement is sufficiently accurate if within 0.1% of the
+        // previous.
+        if measure * 0.999 < prev_measure && prev_measure < measure * 1.001 {
+            return measure;
+        }
+
+        *slot = measure;
+        prev_measure = measure;
+
+        delay_ms *= 2;
+    }
+
+    // If no frequencies were within 0.1% of each other, find the frequency
+    // with the smallest delta.
+    let mut min_delta = f64::INFINITY;
+    let mut result_index = 0;
+
+    for i in 0..TRIES {
+        for j in (i + 1)..TRIES {
+            let delta = (measures[i] - measures[j]).abs();
+
+            if delta < min_delta {
+                min_delta = delta;
+                result_index = i;
+            }
+        }
+    }
+
+    measures[result_index]
+}
+
+fn measure_frequency_once(read: fn() -> u64, delay: Duration) -> f64 {
+    let (start_tsc, start_instant) = tsc_instant_pair(read);
+    std::thread::sleep(delay);
+    let (end_tsc, end_instant) = tsc_instant_pair(read);
+
+    let elapsed_tsc = end_tsc.saturating_sub(start_tsc);
+    let elapsed_duration = end_instant.duration_since(start_instant);
+
+    (elapsed_tsc as f64 / elapsed_duration.as_nanos() as f64) * 1e9
+}
+
+/// Returns a timestamp/instant pair that has a small latency between getting
+/// the two values.
+fn tsc_instant_pair(read: fn() -> u64) -> (u64, Instant) {
+    let mut best_latency = Duration::MAX;
+    let mut best_pair = (0, Instant::now());
+
+    // Make up to 100 attempts to get a low latency pair.
+    for _ in 0..100 {
+        let instant = Instant::now();
+        let tsc = read();
+        let latency = instant.elapsed();
+
+        let pair = (tsc, instant);
+
+        if latency.is_zero() {
+            return pair;
+        }
+
+        if latency < best_latency {
+            best_latency = latency;
+            best_pair = pair;
+        }
+    }
+
+    best_pair
+}
@@ -10,6 +10,9 @@ mod arch;
 #[path = "x86.rs"]
 mod arch;
 
+#[cfg(any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64"))]
+mod measure;
+
 /// [CPU timestamp counter](https://en.wikipedia.org/wiki/Time_Stamp_Counter).
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(transparent)]
@@ -20,8 +23,8 @@ pub(crate) struct TscTimestamp {
 impl TscTimestamp {
     /// Gets the timestamp frequency.
     ///
-    /// On AArch64, this simply reads `cntfrq_el0`. On x86, this measures the
-    /// TSC frequency.
+    /// On AArch64, this reads `cntfrq_el0` and checks it against a measured
+    /// frequency. On x86, this measures the TSC frequency.
     #[inline]
     #[allow(unreachable_code)]
     pub fn frequency() -> Result<NonZeroU64, TscUnavailable> {
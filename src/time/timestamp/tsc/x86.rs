@@ -4,8 +4,6 @@ use std::arch::x86;
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64 as x86;
 
-use std::time::{Duration, Instant};
-
 use crate::time::{fence, TscUnavailable};
 
 #[inline(always)]
@@ -49,7 +47,7 @@ pub(crate) fn frequency() -> Result<u64, TscUnavailable> {
         nominal = nominal_frequency();
     }
 
-    let measured = measure::measure_frequency();
+    let measured = super::measure::measure_frequency(util::rdtsc);
 
     // Use the nominal frequency if within 0.1% of the measured frequency.
     //
@@ -257,92 +255,6 @@ mod util {
         ];
 
         // SAFETY: Converting from `u32` to bytes.
-        Some(unsafe { std::mem::transmute(result) })
-    }
-}
-
-mod measure {
-    use super::*;
-
-    /// Returns the TSC frequency by measuring it.
-    pub fn measure_frequency() -> f64 {
-        const TRIES: usize = 8;
-
-        // Start with delay of 1ms up to 256ms (2^TRIES).
-        let mut delay_ms = 1;
-
-        let mut prev_measure = f64::NEG_INFINITY;
-        let mut measures = [0.0; TRIES];
-
-        for slot in &mut measures {
-            let measure = measure_frequency_once(Duration::from_millis(delay_ms));
-
-            // This measurement is sufficiently accurate if within 0.1% of the
-            // previous.
-            if measure * 0.999 < prev_measure && prev_measure < measure * 1.001 {
-                return measure;
-            }
-
-            *slot = measure;
-            prev_measure = measure;
-
-            delay_ms *= 2;
-        }
-
-        // If no frequencies were within 0.1% of each other, find the frequency
-        // with the smallest delta.
-        let mut min_delta = f64::INFINITY;
-        let mut result_index = 0;
-
-        for i in 0..TRIES {
-            for j in (i + 1)..TRIES {
-                let delta = (measures[i] - measures[j]).abs();
-
-                if delta < min_delta {
-                    min_delta = delta;
-                    result_index = i;
-                }
-            }
-        }
-
-        measures[result_index]
-    }
-
-    fn measure_frequency_once(delay: Duration) -> f64 {
-        let (start_tsc, start_instant) = tsc_instant_pair();
-        std::thread::sleep(delay);
-        let (end_tsc, end_instant) = tsc_instant_pair();
-
-        let elapsed_tsc = end_tsc.saturating_sub(start_tsc);
-        let elapsed_duration = end_instant.duration_since(start_instant);
-
-        (elapsed_tsc as f64 / elapsed_duration.as_nanos() as f64) * 1e9
-    }
-
-    /// Returns a timestamp/instant pair that has a small latency between
-    /// getting the two values.
-    fn tsc_instant_pair() -> (u64, Instant) {
-        let mut best_latency = Duration::MAX;
-        let mut best_pair = (0, Instant::now());
-
-        // Make up to 100 attempts to get a low latency pair.
-        for _ in 0..100 {
-            let instant = Instant::now();
-            let tsc = util::rdtsc();
-            let latency = instant.elapsed();
-
-            let pair = (tsc, instant);
-
-            if latency.is_zero() {
-                return pair;
-            }
-
-            if latency < best_latency {
-                best_latency = latency;
-                best_pair = pair;
-            }
-        }
-
-        best_pair
+        Some(unsafe { std::mem::transmute::<[[u32; 4]; 3], [u8; 48]>(result) })
     }
 }
@@ -1,12 +1,16 @@
 //! Happy little trees.
 
-use std::{io::Write, iter::repeat};
+use std::{borrow::Cow, fmt, io::Write, iter::repeat, num::NonZeroU64};
 
 use crate::{
     alloc::{AllocOp, AllocTally},
+    config::{GridFormat, GridMetric, OutputFormat, SignificanceTest, TableStyle},
     counter::{AnyCounter, BytesFormat, KnownCounterKind},
-    stats::{Stats, StatsSet},
-    util,
+    entry::EntryLocation,
+    grid::Grids,
+    stats::{Comparison, RunVariance, Stats, StatsSet},
+    time::FineDuration,
+    util::{self, fmt::DisplayThroughput},
 };
 
 const TREE_COL_BUF: usize = 2;
@@ -17,10 +21,25 @@ pub(crate) struct TreePainter {
     /// information should be left-padded to start at this column.
     max_name_span: usize,
 
+    /// Whether `max_name_span` is fixed, in which case longer names are
+    /// truncated rather than widening the name column.
+    fixed_name_span: bool,
+
     column_widths: [usize; TreeColumn::COUNT],
 
+    /// Which columns are shown, since optional columns are hidden to fit the
+    /// terminal width.
+    visible_columns: [bool; TreeColumn::COUNT],
+
     depth: usize,
 
+    /// The depth at which parents are collapsed into their children's names.
+    collapse_depth: usize,
+
+    /// Names of collapsed parents and whether each is the last of its
+    /// siblings, outermost first.
+    collapsed: Vec<(String, bool)>,
+
     /// The current prefix to the name and content, e.g.
     /// <code>│     │  </code> for three levels of nesting with the second level
     /// being on the last node.
@@ -28,16 +47,88 @@ pub(crate) struct TreePainter {
 
     /// Buffer for writing to before printing to stdout.
     write_buf: String,
+
+    format: OutputFormat,
+
+    /// Characters used to draw the tree and table.
+    style: TableStyle,
+
+    /// The number of significant figures for durations and throughput.
+    sig_figs: usize,
+
+    /// The separator between groups of thousands in counts.
+    thousands_separator: Option<char>,
+
+    /// The frequency of the CPU timestamp counter if it is the timer, used to
+    /// convert times into cycles.
+    tsc_frequency: Option<NonZeroU64>,
+
+    /// Names of the current node and its ancestors, used by formats other than
+    /// [`OutputFormat::Pretty`].
+    path: Vec<String>,
+
+    /// Totals across all leaves with statistics.
+    totals: Totals,
+
+    /// The number of variants that ran successfully in `--test` mode.
+    test_pass_count: usize,
+
+    /// Paths of variants that panicked in `--test` mode.
+    test_failures: Vec<String>,
+
+    /// Summary grids of benchmarks over both types and arguments, if enabled
+    /// with `--grid`.
+    grids: Option<Grids>,
+}
+
+/// Totals across all benchmarks of a run.
+#[derive(Default)]
+struct Totals {
+    bench_count: usize,
+
+    /// The sum of all measured sample times.
+    measured_time: FineDuration,
+
+    /// The sum of counts across all iterations.
+    counts: [Option<u128>; KnownCounterKind::COUNT],
+
+    /// The median throughput of each benchmark with counts, per second.
+    rates: [Vec<f64>; KnownCounterKind::COUNT],
+
+    /// The end-to-end time and measured time of each benchmark by path.
+    wall_times: Vec<(String, FineDuration, FineDuration)>,
 }
 
 impl TreePainter {
-    pub fn new(max_name_span: usize, column_widths: [usize; TreeColumn::COUNT]) -> Self {
+    pub fn new(
+        max_name_span: usize,
+        column_widths: [usize; TreeColumn::COUNT],
+        format: OutputFormat,
+        style: TableStyle,
+        sig_figs: usize,
+        collapse_depth: usize,
+        name_width: Option<usize>,
+    ) -> Self {
         Self {
-            max_name_span,
+            max_name_span: name_width.unwrap_or(max_name_span),
+            fixed_name_span: name_width.is_some(),
             column_widths,
+            visible_columns: [true; TreeColumn::COUNT],
             depth: 0,
+            collapse_depth,
+            collapsed: Vec::new(),
             current_prefix: String::new(),
             write_buf: String::new(),
+            format,
+            style,
+            sig_figs,
+            thousands_separator: None,
+            tsc_frequency: None,
+            path: Vec::new(),
+            totals: Totals::default(),
+            test_pass_count: 0,
+            test_failures: Vec::new(),
+            grids: None,
         }
     }
 }
@@ -45,20 +136,26 @@ impl TreePainter {
 impl TreePainter {
     /// Enter a parent node.
     pub fn start_parent(&mut self, name: &str, is_last: bool) {
+        if !self.format.is_pretty() {
+            self.path.push(name.to_owned());
+            return;
+        }
+
         let is_top_level = self.depth == 0;
+
+        if !is_top_level && self.depth >= self.collapse_depth {
+            self.collapsed.push((name.to_owned(), is_last));
+            return;
+        }
+
         let has_columns = self.has_columns();
 
+        let branch = if is_top_level { "" } else { self.style.branch(is_last) };
+        let name = self.fit_name(branch, name, has_columns);
+
         let buf = &mut self.write_buf;
         buf.clear();
-
-        let branch = if is_top_level {
-            ""
-        } else if !is_last {
-            "├─ "
-        } else {
-            "╰─ "
-        };
-        buf.extend([self.current_prefix.as_str(), branch, name]);
+        buf.extend([self.current_prefix.as_str(), branch, &name]);
 
         // Right-pad name if `has_columns`
         if has_columns {
@@ -75,12 +172,17 @@ impl TreePainter {
         // Write column headings.
         if has_columns && is_top_level {
             let names = TreeColumnData::from_fn(TreeColumn::name);
-            names.write(buf, &mut self.column_widths);
+            names.write(buf, &mut self.column_widths, self.visible_columns, self.style);
         }
 
         // Write column spacers.
         if has_columns && !is_top_level {
-            TreeColumnData([""; TreeColumn::COUNT]).write(buf, &mut self.column_widths);
+            TreeColumnData([""; TreeColumn::COUNT]).write(
+                buf,
+                &mut self.column_widths,
+                self.visible_columns,
+                self.style,
+            );
         }
 
         println!("{buf}");
@@ -88,12 +190,21 @@ impl TreePainter {
         self.depth += 1;
 
         if !is_top_level {
-            self.current_prefix.push_str(if !is_last { "│  " } else { "   " });
+            self.current_prefix.push_str(self.style.indent(is_last));
         }
     }
 
     /// Exit the current parent node.
     pub fn finish_parent(&mut self) {
+        if !self.format.is_pretty() {
+            self.path.pop();
+            return;
+        }
+
+        if self.collapsed.pop().is_some() {
+            return;
+        }
+
         self.depth -= 1;
 
         // Improve legibility for multiple top-level parents.
@@ -114,13 +225,19 @@ impl TreePainter {
     ///
     /// This semantically combines start/finish operations.
     pub fn ignore_leaf(&mut self, name: &str, is_last: bool) {
+        if !self.format.is_pretty() {
+            return;
+        }
+
         let has_columns = self.has_columns();
+        let (name, is_last) = self.collapse_name(name, is_last);
+
+        let branch = self.style.branch(is_last);
+        let name = self.fit_name(branch, &name, has_columns);
 
         let buf = &mut self.write_buf;
         buf.clear();
-
-        let branch = if !is_last { "├─ " } else { "╰─ " };
-        buf.extend([self.current_prefix.as_str(), branch, name]);
+        buf.extend([self.current_prefix.as_str(), branch, &name]);
 
         // Right-pad buffer.
         {
@@ -135,7 +252,12 @@ impl TreePainter {
         }
 
         if has_columns {
-            TreeColumnData::from_first("(ignored)").write(buf, &mut self.column_widths);
+            TreeColumnData::from_first("(ignored)").write(
+                buf,
+                &mut self.column_widths,
+                self.visible_columns,
+                self.style,
+            );
         } else {
             buf.push_str("(ignored)");
         }
@@ -145,13 +267,20 @@ impl TreePainter {
 
     /// Enter a leaf node.
     pub fn start_leaf(&mut self, name: &str, is_last: bool) {
+        if !self.format.is_pretty() {
+            self.path.push(name.to_owned());
+            return;
+        }
+
         let has_columns = self.has_columns();
+        let (name, is_last) = self.collapse_name(name, is_last);
+
+        let branch = self.style.branch(is_last);
+        let name = self.fit_name(branch, &name, has_columns);
 
         let buf = &mut self.write_buf;
         buf.clear();
-
-        let branch = if !is_last { "├─ " } else { "╰─ " };
-        buf.extend([self.current_prefix.as_str(), branch, name]);
+        buf.extend([self.current_prefix.as_str(), branch, &name]);
 
         // Right-pad buffer if this leaf will have info displayed.
         if has_columns {
@@ -171,11 +300,60 @@ impl TreePainter {
 
     /// Exit the current leaf node.
     pub fn finish_empty_leaf(&mut self) {
+        if !self.format.is_pretty() {
+            println!("{}", self.path.join("::"));
+            self.path.pop();
+            return;
+        }
+
         println!();
     }
 
+    /// Exit the current leaf node, emitting whether it passed in `--test`
+    /// mode.
+    pub fn finish_test_leaf(&mut self, leaf_path: &str, passed: bool) {
+        let status = if passed { "ok" } else { "FAILED" };
+
+        if passed {
+            self.test_pass_count += 1;
+        } else {
+            self.test_failures.push(leaf_path.to_owned());
+        }
+
+        if !self.format.is_pretty() {
+            println!("{}\t{status}", self.path.join("::"));
+            self.path.pop();
+            return;
+        }
+
+        // Align with `(ignored)` after the name written by `start_leaf`.
+        let buf_len = self.write_buf.chars().count();
+        let pad_len = TREE_COL_BUF + self.max_name_span.saturating_sub(buf_len);
+
+        println!("{:pad_len$}{status}", "");
+    }
+
     /// Exit the current leaf node, emitting statistics.
-    pub fn finish_leaf(&mut self, is_last: bool, stats: &Stats, bytes_format: BytesFormat) {
+    pub fn finish_leaf(
+        &mut self,
+        is_last: bool,
+        stats: &Stats,
+        comparison: Option<&Comparison>,
+        custom_meta: &[(&str, &str)],
+        bytes_format: BytesFormat,
+    ) {
+        self.totals.add(stats);
+
+        if !self.format.is_pretty() {
+            self.finish_terse_leaf(stats, custom_meta, bytes_format);
+            return;
+        }
+
+        let is_last = self.collapsed_is_last(is_last);
+
+        let sig_figs = self.sig_figs;
+        let thousands_separator = self.thousands_separator;
+
         let buf = &mut self.write_buf;
         buf.clear();
 
@@ -201,14 +379,17 @@ impl TreePainter {
             Some(AllocTally {
                 count: column_tallies.map(|tally| {
                     if let Some((prefix, tally)) = tally {
-                        format!("{prefix}{}", util::fmt::format_f64(tally.count, 4))
+                        let count = util::fmt::format_f64(tally.count, sig_figs);
+                        let count = util::fmt::group_thousands(&count, thousands_separator);
+                        format!("{prefix}{count}")
                     } else {
                         String::new()
                     }
                 }),
                 size: column_tallies.map(|tally| {
                     if let Some((prefix, tally)) = tally {
-                        format!("{prefix}{}", util::fmt::format_bytes(tally.size, 4, bytes_format))
+                        let size = util::fmt::format_bytes(tally.size, sig_figs, bytes_format);
+                        format!("{prefix}{size}")
                     } else {
                         String::new()
                     }
@@ -216,6 +397,20 @@ impl TreePainter {
             })
         });
 
+        // Serialize cycles early so we can resize columns early.
+        let serialized_cycles = self.tsc_frequency.map(|frequency| {
+            TreeColumn::ALL
+                .map(|column| -> Option<String> {
+                    let time = column.get_stat(&stats.time)?;
+                    let cycles = time.picos as f64 * frequency.get() as f64 / 1e12;
+
+                    let cycles = util::fmt::format_f64(cycles, sig_figs);
+                    let cycles = util::fmt::group_thousands(&cycles, thousands_separator);
+                    Some(format!("{cycles} cycles"))
+                })
+                .map(Option::unwrap_or_default)
+        });
+
         // Serialize counter stats early so we can resize columns early.
         let serialized_counters = KnownCounterKind::ALL.map(|counter_kind| {
             let counter_stats = stats.get_counts(counter_kind);
@@ -225,11 +420,8 @@ impl TreePainter {
                     let count = *column.get_stat(counter_stats?)?;
                     let time = *column.get_stat(&stats.time)?;
 
-                    Some(
-                        AnyCounter::known(counter_kind, count)
-                            .display_throughput(time, bytes_format)
-                            .to_string(),
-                    )
+                    let counter = AnyCounter::known(counter_kind, count);
+                    Some(format!("{:.sig_figs$}", counter.display_throughput(time, bytes_format)))
                 })
                 .map(Option::unwrap_or_default)
         });
@@ -237,7 +429,7 @@ impl TreePainter {
         for column in TreeColumn::time_stats() {
             let width = &mut self.column_widths[column as usize];
 
-            for counter in &serialized_counters {
+            for counter in serialized_cycles.iter().chain(&serialized_counters) {
                 let s = &counter[column as usize];
                 *width = (*width).max(s.chars().count());
             }
@@ -254,7 +446,7 @@ impl TreePainter {
 
         // Write time stats with iter and sample counts.
         TreeColumnData::from_fn(|column| -> String {
-            let stat: &dyn ToString = match column {
+            let stat: &dyn fmt::Display = match column {
                 TreeColumn::Fastest => &stats.time.fastest,
                 TreeColumn::Slowest => &stats.time.slowest,
                 TreeColumn::Median => &stats.time.median,
@@ -262,17 +454,24 @@ impl TreePainter {
                 TreeColumn::Samples => &stats.sample_count,
                 TreeColumn::Iters => &stats.iter_count,
             };
-            stat.to_string()
+            // Precision is ignored by integer counts.
+            let stat = format!("{stat:.sig_figs$}");
+
+            if column.is_time_stat() {
+                stat
+            } else {
+                util::fmt::group_thousands(&stat, thousands_separator).into_owned()
+            }
         })
         .as_ref::<str>()
-        .write(buf, &mut self.column_widths);
+        .write(buf, &mut self.column_widths, self.visible_columns, self.style);
 
         println!("{buf}");
 
-        // Write counter stats.
-        let counter_stats = serialized_counters.map(TreeColumnData);
-        for counter_kind in KnownCounterKind::ALL {
-            let counter_stats = counter_stats[counter_kind as usize].as_ref::<str>();
+        // Write cycles and counter stats.
+        let serialized_rows = serialized_cycles.into_iter().chain(serialized_counters);
+        for row_stats in serialized_rows.map(TreeColumnData) {
+            let counter_stats = row_stats.as_ref::<str>();
 
             // Skip empty rows.
             if counter_stats.0.iter().all(|s| s.is_empty()) {
@@ -283,7 +482,7 @@ impl TreePainter {
             buf.push_str(&self.current_prefix);
 
             if !is_last {
-                buf.push('│');
+                buf.push(self.style.vertical());
             }
 
             // Right-pad buffer.
@@ -298,7 +497,7 @@ impl TreePainter {
                 }
             };
 
-            counter_stats.write(buf, &mut self.column_widths);
+            counter_stats.write(buf, &mut self.column_widths, self.visible_columns, self.style);
             println!("{buf}");
         }
 
@@ -312,7 +511,7 @@ impl TreePainter {
             buf.push_str(&self.current_prefix);
 
             if !is_last {
-                buf.push('│');
+                buf.push(self.style.vertical());
             }
 
             // Right-pad buffer.
@@ -327,7 +526,12 @@ impl TreePainter {
                 }
             };
 
-            TreeColumnData::from_first(op.prefix()).write(buf, &mut self.column_widths);
+            TreeColumnData::from_first(op.prefix()).write(
+                buf,
+                &mut self.column_widths,
+                self.visible_columns,
+                self.style,
+            );
             println!("{buf}");
 
             for value in tallies.as_array() {
@@ -335,7 +539,7 @@ impl TreePainter {
                 buf.push_str(&self.current_prefix);
 
                 if !is_last {
-                    buf.push('│');
+                    buf.push(self.style.vertical());
                 }
 
                 // Right-pad buffer.
@@ -350,12 +554,587 @@ impl TreePainter {
                     }
                 };
 
-                TreeColumnData::from_fn(|column| value[column as usize].as_str())
-                    .write(buf, &mut self.column_widths);
+                TreeColumnData::from_fn(|column| value[column as usize].as_str()).write(
+                    buf,
+                    &mut self.column_widths,
+                    self.visible_columns,
+                    self.style,
+                );
 
                 println!("{buf}");
             }
         }
+
+        if let Some(run_variance) = &stats.run_variance {
+            self.write_run_variance(is_last, run_variance);
+        }
+
+        if let Some(cpu_migrations) = stats.many_cpu_migrations() {
+            self.write_cpu_migrations(is_last, cpu_migrations);
+        }
+
+        if let Some(fastest_iter) = stats.fastest_iter {
+            self.write_fastest_iter(is_last, fastest_iter);
+        }
+
+        if let Some(child_cpu_time) = stats.child_cpu_time {
+            self.write_child_cpu_time(is_last, child_cpu_time);
+        }
+
+        if stats.rejected_count > 0 {
+            self.write_rejected_count(is_last, stats.rejected_count);
+        }
+
+        if let Some(comparison) = comparison {
+            self.write_comparison(is_last, comparison);
+        }
+
+        if !custom_meta.is_empty() {
+            self.write_meta(is_last, custom_meta);
+        }
+    }
+
+    /// Writes how much of the sample variance comes from between repeated
+    /// runs versus within each run.
+    fn write_run_variance(&mut self, is_last: bool, run_variance: &RunVariance) {
+        let share = |share: f64| format!("{:.1}%", share * 100.0);
+
+        let rows: [TreeColumnData<String>; 2] = [
+            TreeColumnData::from_first("run variance:".to_owned()),
+            TreeColumnData::from_fn(|column| match column {
+                TreeColumn::Fastest => format!("  between {}", share(run_variance.between)),
+                TreeColumn::Slowest => format!("within {}", share(run_variance.within)),
+                _ => String::new(),
+            }),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Flags that the benchmarking thread often migrated between CPUs.
+    fn write_cpu_migrations(&mut self, is_last: bool, cpu_migrations: u64) {
+        let cpu_migrations =
+            util::fmt::group_thousands(&cpu_migrations.to_string(), self.thousands_separator)
+                .into_owned();
+
+        let rows: [TreeColumnData<String>; 2] = [
+            TreeColumnData::from_first("migrations:".to_owned()),
+            TreeColumnData::from_first(format!("  {cpu_migrations}")),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Writes the fastest single iteration under the fastest sample average.
+    fn write_fastest_iter(&mut self, is_last: bool, fastest_iter: FineDuration) {
+        let sig_figs = self.sig_figs;
+
+        let rows: [TreeColumnData<String>; 2] = [
+            TreeColumnData::from_first("fastest iter:".to_owned()),
+            TreeColumnData::from_first(format!("  {fastest_iter:.sig_figs$}")),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Writes the mean CPU time of child processes per iteration.
+    fn write_child_cpu_time(&mut self, is_last: bool, child_cpu_time: FineDuration) {
+        let sig_figs = self.sig_figs;
+
+        let rows: [TreeColumnData<String>; 2] = [
+            TreeColumnData::from_first("child cpu:".to_owned()),
+            TreeColumnData::from_first(format!("  {child_cpu_time:.sig_figs$}")),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Writes how many samples were rejected as clock anomalies.
+    fn write_rejected_count(&mut self, is_last: bool, rejected_count: u32) {
+        let rejected_count =
+            util::fmt::group_thousands(&rejected_count.to_string(), self.thousands_separator)
+                .into_owned();
+
+        let rows: [TreeColumnData<String>; 2] = [
+            TreeColumnData::from_first("rejected:".to_owned()),
+            TreeColumnData::from_first(format!("  {rejected_count}")),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Writes relative changes against a baseline and whether the sample
+    /// distribution changed significantly.
+    fn write_comparison(&mut self, is_last: bool, comparison: &Comparison) {
+        let change = |change: f64| format!("{:+.2}%", change * 100.0);
+        let time_change = &comparison.time_change;
+
+        let test = comparison.test;
+        let (test_name, statistic_name) = match test.test {
+            SignificanceTest::Ks => ("KS", "D"),
+            SignificanceTest::MannWhitney => ("MWU", "U"),
+        };
+        let test_summary = if test.p_value < 0.001 {
+            format!("  {test_name} p<0.001")
+        } else {
+            format!("  {test_name} p={:.3}", test.p_value)
+        };
+
+        let rows: [TreeColumnData<String>; 3] = [
+            TreeColumnData::from_first("baseline:".to_owned()),
+            TreeColumnData::from_fn(|column| match column {
+                TreeColumn::Fastest => format!("  {}", change(time_change.fastest)),
+                TreeColumn::Slowest => change(time_change.slowest),
+                TreeColumn::Median => change(time_change.median),
+                TreeColumn::Mean => change(time_change.mean),
+                TreeColumn::Samples | TreeColumn::Iters => String::new(),
+            }),
+            TreeColumnData::from_fn(|column| match column {
+                TreeColumn::Fastest => test_summary.clone(),
+                TreeColumn::Slowest => {
+                    format!("{statistic_name}={}", util::fmt::format_f64(test.statistic, 3))
+                }
+                TreeColumn::Median if test.is_significant() => "changed".to_owned(),
+                TreeColumn::Median => "unchanged".to_owned(),
+                _ => String::new(),
+            }),
+        ];
+
+        self.write_detail_rows(is_last, rows);
+    }
+
+    /// Writes a row of `key=value` for each custom metadata pair.
+    ///
+    /// Pairs may be longer than the first column, so they are written without
+    /// columns to not widen it for later rows.
+    fn write_meta(&mut self, is_last: bool, custom_meta: &[(&str, &str)]) {
+        let buf = &mut self.write_buf;
+
+        let pairs = custom_meta.iter().map(|(key, value)| format!("  {key}={value}"));
+        for row in std::iter::once("meta:".to_owned()).chain(pairs) {
+            buf.clear();
+            buf.push_str(&self.current_prefix);
+
+            if !is_last {
+                buf.push(self.style.vertical());
+            }
+
+            let pad_len = TREE_COL_BUF + self.max_name_span.saturating_sub(buf.chars().count());
+            buf.extend(repeat(' ').take(pad_len));
+            buf.push_str(&row);
+
+            println!("{buf}");
+        }
+    }
+
+    /// Writes rows of details about the current leaf below it.
+    fn write_detail_rows<const N: usize>(
+        &mut self,
+        is_last: bool,
+        rows: [TreeColumnData<String>; N],
+    ) {
+        let buf = &mut self.write_buf;
+
+        for row in rows {
+            buf.clear();
+            buf.push_str(&self.current_prefix);
+
+            if !is_last {
+                buf.push(self.style.vertical());
+            }
+
+            // Right-pad buffer.
+            {
+                let buf_len = buf.chars().count();
+                let max_span = self.max_name_span;
+                let pad_len = TREE_COL_BUF + self.max_name_span.saturating_sub(buf_len);
+                buf.extend(repeat(' ').take(pad_len));
+
+                if buf_len > max_span {
+                    self.max_name_span = buf_len;
+                }
+            };
+
+            row.as_ref::<str>().write(
+                buf,
+                &mut self.column_widths,
+                self.visible_columns,
+                self.style,
+            );
+            println!("{buf}");
+        }
+    }
+
+    /// Writes `full_path<TAB>median<TAB>throughput`, with a throughput entry
+    /// for each counter and a `key=value` entry for each custom metadata pair.
+    fn finish_terse_leaf(
+        &mut self,
+        stats: &Stats,
+        custom_meta: &[(&str, &str)],
+        bytes_format: BytesFormat,
+    ) {
+        use std::fmt::Write;
+
+        let buf = &mut self.write_buf;
+        buf.clear();
+
+        let sig_figs = self.sig_figs;
+
+        buf.push_str(&self.path.join("::"));
+        _ = write!(buf, "\t{:.sig_figs$}", stats.time.median);
+
+        for counter_kind in KnownCounterKind::ALL {
+            let Some(counts) = stats.get_counts(counter_kind) else {
+                continue;
+            };
+
+            let counter = AnyCounter::known(counter_kind, counts.median);
+            let throughput = counter.display_throughput(stats.time.median, bytes_format);
+
+            _ = write!(buf, "\t{throughput:.sig_figs$}");
+        }
+
+        for (key, value) in custom_meta {
+            _ = write!(buf, "\t{key}={value}");
+        }
+
+        println!("{buf}");
+        self.path.pop();
+    }
+
+    /// Writes a JSON object describing a listed leaf for
+    /// [`OutputFormat::Json`].
+    ///
+    /// This semantically combines start/finish operations.
+    pub fn list_json_leaf(
+        &mut self,
+        name: &str,
+        location: EntryLocation,
+        ignored: bool,
+        parameters: &[(&str, &str)],
+        custom_meta: &[(&str, &str)],
+        threads: Option<&[usize]>,
+    ) {
+        use std::fmt::Write;
+
+        let buf = &mut self.write_buf;
+        buf.clear();
+
+        self.path.push(name.to_owned());
+
+        buf.push_str("{\"path\":");
+        util::fmt::push_json_str(buf, &self.path.join("::"));
+
+        buf.push_str(",\"file\":");
+        util::fmt::push_json_str(buf, location.file);
+
+        _ = write!(buf, ",\"line\":{},\"ignored\":{ignored}", location.line);
+
+        buf.push_str(",\"parameters\":{");
+
+        for (i, (key, value)) in parameters.iter().enumerate() {
+            if i != 0 {
+                buf.push(',');
+            }
+            util::fmt::push_json_str(buf, key);
+            buf.push(':');
+            util::fmt::push_json_str(buf, value);
+        }
+
+        if let Some(threads) = threads {
+            if !parameters.is_empty() {
+                buf.push(',');
+            }
+            _ = write!(buf, "\"threads\":{threads:?}");
+        }
+
+        buf.push_str("},\"meta\":{");
+
+        for (i, (key, value)) in custom_meta.iter().enumerate() {
+            if i != 0 {
+                buf.push(',');
+            }
+            util::fmt::push_json_str(buf, key);
+            buf.push(':');
+            util::fmt::push_json_str(buf, value);
+        }
+
+        buf.push_str("}}");
+
+        println!("{buf}");
+        self.path.pop();
+    }
+
+    /// Prints failed variants and counts of `--test` mode results, and returns
+    /// `true` if all variants passed.
+    pub fn finish_test(&self) -> bool {
+        let failures = &self.test_failures;
+
+        if self.format.is_pretty() {
+            if !failures.is_empty() {
+                println!("failures:");
+                for failure in failures {
+                    println!("    {failure}");
+                }
+                println!();
+            }
+
+            println!(
+                "test result: {}. {} passed; {} failed",
+                if failures.is_empty() { "ok" } else { "FAILED" },
+                self.test_pass_count,
+                failures.len(),
+            );
+        }
+
+        failures.is_empty()
+    }
+
+    /// Emits totals across all benchmarks that were run.
+    pub fn finish(&self, wall_time: FineDuration, bytes_format: BytesFormat) {
+        let totals = &self.totals;
+        let sig_figs = self.sig_figs;
+
+        if !self.format.is_pretty() || totals.bench_count == 0 {
+            return;
+        }
+
+        let mut lines: Vec<(&str, String)> = vec![
+            (
+                "benchmarks",
+                util::fmt::group_thousands(
+                    &totals.bench_count.to_string(),
+                    self.thousands_separator,
+                )
+                .into_owned(),
+            ),
+            ("measured time", format!("{:.sig_figs$}", totals.measured_time)),
+            ("wall time", format!("{wall_time:.sig_figs$}")),
+        ];
+
+        for counter_kind in KnownCounterKind::ALL {
+            if let Some(count) = totals.counts[counter_kind as usize] {
+                let name = match counter_kind {
+                    KnownCounterKind::Bytes => "bytes",
+                    KnownCounterKind::Chars => "chars",
+                    KnownCounterKind::Items => "items",
+                    KnownCounterKind::Lines => "lines",
+                };
+
+                let count =
+                    util::fmt::format_count(count as f64, sig_figs, counter_kind, bytes_format);
+                lines.push((name, count));
+            }
+        }
+
+        // Summarize throughput with the harmonic mean, since the arithmetic
+        // mean of rates is dominated by the fastest benchmarks.
+        for counter_kind in KnownCounterKind::ALL {
+            let rates = &totals.rates[counter_kind as usize];
+            if rates.len() < 2 {
+                continue;
+            }
+
+            let Some(rate) = crate::stats::harmonic_mean(rates) else {
+                continue;
+            };
+
+            let name = match counter_kind {
+                KnownCounterKind::Bytes => "bytes/s",
+                KnownCounterKind::Chars => "chars/s",
+                KnownCounterKind::Items => "items/s",
+                KnownCounterKind::Lines => "lines/s",
+            };
+
+            // Throughput of a single unit per the time that a unit takes.
+            let counter = AnyCounter::known(counter_kind, 1);
+            let throughput =
+                DisplayThroughput { counter: &counter, picos: 1e12 / rate, bytes_format };
+
+            lines.push((name, format!("{throughput:.sig_figs$} (harmonic mean)")));
+        }
+
+        let name_width = lines.iter().map(|(name, _)| name.len()).max().unwrap_or_default();
+
+        println!("Total:");
+        for (name, value) in lines {
+            println!("  {name:name_width$}  {}", self.style.units(&value));
+        }
+
+        self.finish_wall_times();
+    }
+
+    /// Emits the benchmarks that took the longest end-to-end, which helps with
+    /// finding expensive setup and budgeting CI time.
+    fn finish_wall_times(&self) {
+        const MAX_LISTED: usize = 5;
+
+        let sig_figs = self.sig_figs;
+
+        let mut wall_times: Vec<_> = self.totals.wall_times.iter().collect();
+        if wall_times.len() < 2 {
+            return;
+        }
+
+        wall_times.sort_by(|(_, a, _), (_, b, _)| b.cmp(a));
+        wall_times.truncate(MAX_LISTED);
+
+        let path_width =
+            wall_times.iter().map(|(path, ..)| path.chars().count()).max().unwrap_or_default();
+        let lines: Vec<(&str, String, String)> = wall_times
+            .iter()
+            .map(|(path, wall_time, measured_time)| {
+                (
+                    path.as_str(),
+                    format!("{wall_time:.sig_figs$}"),
+                    format!("{measured_time:.sig_figs$}"),
+                )
+            })
+            .collect();
+        let time_width =
+            lines.iter().map(|(_, wall_time, _)| wall_time.chars().count()).max().unwrap_or(0);
+
+        println!();
+        println!("Longest wall times:");
+        for (path, wall_time, measured_time) in lines {
+            let line = format!("{wall_time:time_width$}  (measured {measured_time})");
+            println!("  {path:path_width$}  {}", self.style.units(&line));
+        }
+    }
+
+    /// Returns `name` truncated to fit within a fixed name column after the
+    /// current prefix and `branch`.
+    fn fit_name<'n>(&self, branch: &str, name: &'n str, has_columns: bool) -> Cow<'n, str> {
+        if !self.fixed_name_span || !has_columns {
+            return Cow::Borrowed(name);
+        }
+
+        let prefix_len = self.current_prefix.chars().count() + branch.chars().count();
+        let max_chars = self.max_name_span.saturating_sub(prefix_len);
+        util::fmt::truncate_middle(name, max_chars, self.style.ellipsis())
+    }
+
+    /// Returns `name` prefixed by the names of collapsed parents, and whether
+    /// it is the last of its displayed siblings.
+    fn collapse_name<'n>(&self, name: &'n str, is_last: bool) -> (Cow<'n, str>, bool) {
+        let is_last = self.collapsed_is_last(is_last);
+
+        if self.collapsed.is_empty() {
+            return (Cow::Borrowed(name), is_last);
+        }
+
+        let mut full_name = String::new();
+        for (parent, _) in &self.collapsed {
+            full_name.extend([parent.as_str(), "::"]);
+        }
+        full_name.push_str(name);
+
+        (Cow::Owned(full_name), is_last)
+    }
+
+    /// Returns whether a node is the last of its displayed siblings, which
+    /// requires its collapsed parents to also be last.
+    fn collapsed_is_last(&self, is_last: bool) -> bool {
+        is_last && self.collapsed.iter().all(|&(_, is_last)| is_last)
+    }
+
+    /// Records how long the benchmark at `bench_path` took end-to-end,
+    /// including tuning and input generation, versus its measured time.
+    pub fn record_wall_time(
+        &mut self,
+        bench_path: String,
+        wall_time: FineDuration,
+        measured_time: FineDuration,
+    ) {
+        self.totals.wall_times.push((bench_path, wall_time, measured_time));
+    }
+
+    /// Records the statistics of a benchmark variant for summary grids, if
+    /// enabled.
+    pub fn record_grid_cell(&mut self, path: &str, type_name: &str, arg_name: &str, stats: &Stats) {
+        if let Some(grids) = &mut self.grids {
+            grids.record(path, type_name, arg_name, stats);
+        }
+    }
+
+    /// Prints summary grids of benchmarks over both types and arguments, if any
+    /// were recorded.
+    pub fn finish_grids(&self, format: GridFormat) {
+        // Grids would corrupt machine-readable output.
+        if !self.format.is_pretty() {
+            return;
+        }
+
+        let Some(grids) = self.grids.as_ref().filter(|grids| !grids.is_empty()) else {
+            return;
+        };
+
+        println!();
+        print!("{}", grids.format(format, self.style, self.sig_figs));
+    }
+
+    /// Enables summary grids of `metric`.
+    pub fn set_grid_metric(&mut self, metric: GridMetric) {
+        self.grids = Some(Grids::new(metric));
+    }
+
+    /// Sets the separator between groups of thousands in counts.
+    pub fn set_thousands_separator(&mut self, separator: Option<char>) {
+        self.thousands_separator = separator;
+    }
+
+    /// Sets the CPU timestamp counter frequency for showing times in cycles.
+    pub fn set_tsc_frequency(&mut self, frequency: Option<NonZeroU64>) {
+        self.tsc_frequency = frequency;
+    }
+
+    /// Hides optional columns and then truncates names so that lines fit
+    /// within `width` characters.
+    pub fn fit_width(&mut self, width: usize) {
+        /// Columns in the order that they are hidden.
+        const OPTIONAL_COLUMNS: [TreeColumn; 4] =
+            [TreeColumn::Iters, TreeColumn::Samples, TreeColumn::Slowest, TreeColumn::Mean];
+
+        /// The narrowest that names are truncated to.
+        const MIN_NAME_SPAN: usize = 16;
+
+        if !self.format.is_pretty() || !self.has_columns() {
+            return;
+        }
+
+        for column in OPTIONAL_COLUMNS {
+            if self.line_width() <= width {
+                return;
+            }
+            self.visible_columns[column as usize] = false;
+        }
+
+        let table_width = self.line_width() - self.max_name_span;
+        let name_span = width.saturating_sub(table_width).max(MIN_NAME_SPAN);
+
+        if name_span < self.max_name_span {
+            self.max_name_span = name_span;
+            self.fixed_name_span = true;
+        }
+    }
+
+    /// Returns the likely width of a row with statistics.
+    fn line_width(&self) -> usize {
+        let columns = TreeColumn::ALL.into_iter().filter(|&c| self.visible_columns[c as usize]);
+
+        let columns_width: usize = columns
+            .clone()
+            .map(|column| {
+                // The last column is not padded, so assume iteration counts
+                // of at most 7 digits.
+                let width = if column.is_last() { 7 } else { self.column_widths[column as usize] };
+                width.max(column.name().len())
+            })
+            .sum();
+
+        let separators_width = 3 * columns.count().saturating_sub(1);
+
+        self.max_name_span + TREE_COL_BUF + columns_width + separators_width
     }
 
     fn has_columns(&self) -> bool {
@@ -454,8 +1233,18 @@ impl<T> TreeColumnData<T> {
 
 impl TreeColumnData<&str> {
     /// Writes the column data into the buffer.
-    fn write(&self, buf: &mut String, column_widths: &mut [usize; TreeColumn::COUNT]) {
+    fn write(
+        &self,
+        buf: &mut String,
+        column_widths: &mut [usize; TreeColumn::COUNT],
+        visible_columns: [bool; TreeColumn::COUNT],
+        style: TableStyle,
+    ) {
         for (column, value) in self.0.iter().enumerate() {
+            if !visible_columns[column] {
+                continue;
+            }
+
             let is_first = column == 0;
             let is_last = column == TreeColumn::COUNT - 1;
 
@@ -463,7 +1252,7 @@ impl TreeColumnData<&str> {
 
             // Write separator.
             if !is_first {
-                let mut sep = " │ ";
+                let mut sep = style.column_separator();
 
                 // Prevent trailing spaces.
                 if is_last && value_width == 0 {
@@ -473,7 +1262,7 @@ impl TreeColumnData<&str> {
                 buf.push_str(sep);
             }
 
-            buf.push_str(value);
+            buf.push_str(&style.units(value));
 
             // Right-pad remaining width or update column width to new maximum.
             if !is_last {
@@ -484,6 +1273,9 @@ impl TreeColumnData<&str> {
                 }
             }
         }
+
+        // Borderless rows with empty columns would otherwise end in spaces.
+        buf.truncate(buf.trim_end().len());
     }
 }
 
@@ -496,3 +1288,173 @@ impl<T> TreeColumnData<T> {
         TreeColumnData::from_fn(|column| self.0[column as usize].as_ref())
     }
 }
+
+impl Totals {
+    fn add(&mut self, stats: &Stats) {
+        self.bench_count += 1;
+        self.measured_time += stats.total_time;
+
+        for counter_kind in KnownCounterKind::ALL {
+            if let Some(counts) = stats.get_counts(counter_kind) {
+                let count = (counts.mean as u128).saturating_mul(stats.iter_count as u128);
+
+                let total = self.counts[counter_kind as usize].get_or_insert(0);
+                *total = total.saturating_add(count);
+
+                let secs = stats.time.median.picos as f64 / 1e12;
+                self.rates[counter_kind as usize].push(counts.median as f64 / secs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn painter(format: OutputFormat) -> TreePainter {
+        TreePainter::new(
+            0,
+            [0; TreeColumn::COUNT],
+            format,
+            TableStyle::default(),
+            4,
+            usize::MAX,
+            None,
+        )
+    }
+
+    #[test]
+    fn collapsed_names() {
+        let mut painter = painter(OutputFormat::Pretty);
+        painter.collapse_depth = 1;
+
+        painter.start_parent("suite", true);
+        painter.start_parent("module", false);
+        painter.start_leaf("bench", true);
+        assert_eq!(painter.write_buf, "├─ module::bench");
+
+        // Matches `EntryTree::max_name_span` of the same tree.
+        assert_eq!(painter.write_buf.chars().count(), 16);
+
+        painter.finish_empty_leaf();
+        painter.finish_parent();
+        painter.start_parent("other", true);
+        painter.start_leaf("bench", true);
+        assert_eq!(painter.write_buf, "╰─ other::bench");
+
+        painter.finish_empty_leaf();
+        painter.finish_parent();
+        painter.finish_parent();
+        assert_eq!(painter.depth, 0);
+        assert!(painter.collapsed.is_empty());
+    }
+
+    #[test]
+    fn fit_width() {
+        let column_widths = TreeColumn::ALL.map(|column| if column.is_last() { 0 } else { 9 });
+        let mut painter = TreePainter::new(
+            20,
+            column_widths,
+            OutputFormat::Pretty,
+            TableStyle::default(),
+            4,
+            usize::MAX,
+            None,
+        );
+
+        let hidden_count =
+            |painter: &TreePainter| painter.visible_columns.iter().filter(|&&v| !v).count();
+
+        painter.fit_width(painter.line_width());
+        assert_eq!(hidden_count(&painter), 0);
+
+        // Each column is hidden only once lines stop fitting without it.
+        let drop_order =
+            [TreeColumn::Iters, TreeColumn::Samples, TreeColumn::Slowest, TreeColumn::Mean];
+        for (i, column) in drop_order.into_iter().enumerate() {
+            painter.fit_width(painter.line_width() - 1);
+
+            assert!(!painter.visible_columns[column as usize], "{} is visible", column.name());
+            assert_eq!(hidden_count(&painter), i + 1);
+            assert_eq!(painter.max_name_span, 20);
+            assert!(!painter.fixed_name_span);
+        }
+
+        // The fastest and median columns are kept, and names are truncated.
+        painter.fit_width(0);
+        assert_eq!(hidden_count(&painter), 4);
+        assert_eq!(painter.max_name_span, 16);
+        assert!(painter.fixed_name_span);
+    }
+
+    #[test]
+    fn ascii_style() {
+        let column_widths = TreeColumn::ALL.map(|column| if column.is_last() { 0 } else { 9 });
+        let mut painter = TreePainter::new(
+            20,
+            column_widths,
+            OutputFormat::Pretty,
+            TableStyle::Ascii,
+            4,
+            usize::MAX,
+            Some(12),
+        );
+
+        painter.start_leaf("long_benchmark_name", true);
+        assert!(painter.write_buf.starts_with("`- lon...ame "));
+        assert!(painter.write_buf.is_ascii());
+
+        let mut buf = String::new();
+        TreeColumnData::from_first("1.5 µs").as_ref::<str>().write(
+            &mut buf,
+            &mut [0; TreeColumn::COUNT],
+            [true; TreeColumn::COUNT],
+            TableStyle::Ascii,
+        );
+        assert!(buf.starts_with("1.5 us |"));
+        assert!(buf.is_ascii());
+    }
+
+    #[test]
+    fn meta_rows() {
+        let column_widths = TreeColumn::ALL.map(|column| if column.is_last() { 0 } else { 9 });
+        let mut painter = TreePainter::new(
+            8,
+            column_widths,
+            OutputFormat::Pretty,
+            TableStyle::default(),
+            4,
+            usize::MAX,
+            None,
+        );
+
+        painter.start_leaf("bench", true);
+        painter.write_meta(true, &[("ticket", "https://example.com/PERF-42")]);
+
+        assert_eq!(
+            painter.write_buf,
+            format!("{}  ticket=https://example.com/PERF-42", " ".repeat(8 + TREE_COL_BUF))
+        );
+        assert_eq!(painter.column_widths, column_widths);
+    }
+
+    #[test]
+    fn test_tally() {
+        for format in [OutputFormat::Pretty, OutputFormat::Terse] {
+            let mut painter = painter(format);
+            assert!(painter.finish_test());
+
+            painter.start_parent("group", true);
+            for (name, passed) in [("a", true), ("b", false), ("c", true)] {
+                painter.start_leaf(name, name == "c");
+                painter.finish_test_leaf(&format!("group::{name}"), passed);
+            }
+            painter.finish_parent();
+
+            assert_eq!(painter.test_pass_count, 2);
+            assert_eq!(painter.test_failures, ["group::b"]);
+            assert!(!painter.finish_test());
+        }
+    }
+}
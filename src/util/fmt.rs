@@ -1,7 +1,10 @@
-use std::fmt;
+use std::{borrow::Cow, fmt};
 
 use crate::counter::{AnyCounter, BytesFormat, KnownCounterKind};
 
+/// The number of significant figures used in outputs by default.
+pub(crate) const DEFAULT_SIG_FIGS: usize = 4;
+
 /// Formats an `f64` to the given number of significant figures.
 pub(crate) fn format_f64(val: f64, sig_figs: usize) -> String {
     let mut str = val.to_string();
@@ -47,6 +50,28 @@ pub(crate) fn format_bytes(val: f64, sig_figs: usize, bytes_format: BytesFormat)
     result
 }
 
+/// Formats a counter's count with a scale suffix, such as "1.5 Mitem".
+pub(crate) fn format_count(
+    val: f64,
+    sig_figs: usize,
+    counter_kind: KnownCounterKind,
+    bytes_format: BytesFormat,
+) -> String {
+    let format = match counter_kind {
+        KnownCounterKind::Bytes => ScaleFormat::Bytes(bytes_format),
+        KnownCounterKind::Chars => ScaleFormat::Chars,
+        KnownCounterKind::Items => ScaleFormat::Items,
+        KnownCounterKind::Lines => ScaleFormat::Lines,
+    };
+
+    let (val, scale) = scale_value(val, format.bytes_format());
+
+    let mut result = format_f64(val, sig_figs);
+    result.push(' ');
+    result.push_str(scale.suffix(format));
+    result
+}
+
 pub(crate) struct DisplayThroughput<'a> {
     pub counter: &'a AnyCounter,
     pub picos: f64,
@@ -69,11 +94,12 @@ impl fmt::Display for DisplayThroughput<'_> {
             KnownCounterKind::Bytes => ScaleFormat::BytesThroughput(self.bytes_format),
             KnownCounterKind::Chars => ScaleFormat::CharsThroughput,
             KnownCounterKind::Items => ScaleFormat::ItemsThroughput,
+            KnownCounterKind::Lines => ScaleFormat::LinesThroughput,
         };
 
         let (val, scale) = scale_value(count_per_sec, format.bytes_format());
 
-        let sig_figs = f.precision().unwrap_or(4);
+        let sig_figs = f.precision().unwrap_or(DEFAULT_SIG_FIGS);
 
         let mut str = format_f64(val, sig_figs);
         str.push(' ');
@@ -93,6 +119,72 @@ impl fmt::Display for DisplayThroughput<'_> {
     }
 }
 
+/// Writes `s` as a quoted JSON string.
+pub(crate) fn push_json_str(buf: &mut String, s: &str) {
+    use fmt::Write;
+
+    buf.push('"');
+
+    for ch in s.chars() {
+        match ch {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            '\0'..='\x1F' => _ = write!(buf, "\\u{:04x}", ch as u32),
+            _ => buf.push(ch),
+        }
+    }
+
+    buf.push('"');
+}
+
+/// Inserts `separator` between groups of thousands in the leading digits of
+/// `num`, such as `1234567.5` to `1,234,567.5`.
+pub(crate) fn group_thousands(num: &str, separator: Option<char>) -> Cow<'_, str> {
+    let int_len = num.bytes().take_while(u8::is_ascii_digit).count();
+
+    let Some(separator) = separator.filter(|_| int_len > 3) else {
+        return Cow::Borrowed(num);
+    };
+
+    let (int, rest) = num.split_at(int_len);
+
+    let mut result = String::with_capacity(num.len() + int_len / 3);
+    for (i, digit) in int.chars().enumerate() {
+        if i != 0 && (int_len - i) % 3 == 0 {
+            result.push(separator);
+        }
+        result.push(digit);
+    }
+    result.push_str(rest);
+
+    Cow::Owned(result)
+}
+
+/// Shortens `s` to at most `max_chars` by replacing its middle with `ellipsis`,
+/// which is kept even if `max_chars` is shorter.
+///
+/// The start and end are kept because they usually differ most between
+/// benchmark names, such as a module and a generic type parameter.
+pub(crate) fn truncate_middle<'s>(s: &'s str, max_chars: usize, ellipsis: &str) -> Cow<'s, str> {
+    let len = s.chars().count();
+    if len <= max_chars {
+        return Cow::Borrowed(s);
+    }
+
+    let kept = max_chars.saturating_sub(ellipsis.chars().count());
+    let tail_len = kept / 2;
+    let head_len = kept - tail_len;
+
+    let mut result: String = s.chars().take(head_len).collect();
+    result.push_str(ellipsis);
+    result.extend(s.chars().skip(len - tail_len));
+
+    Cow::Owned(result)
+}
+
 /// Converts a value to the appropriate scale.
 fn scale_value(value: f64, bytes_format: BytesFormat) -> (f64, Scale) {
     let starts = scale_starts(bytes_format);
@@ -128,15 +220,24 @@ pub(crate) enum Scale {
 pub(crate) enum ScaleFormat {
     Bytes(BytesFormat),
     BytesThroughput(BytesFormat),
+    Chars,
     CharsThroughput,
+    Items,
     ItemsThroughput,
+    Lines,
+    LinesThroughput,
 }
 
 impl ScaleFormat {
     pub fn bytes_format(self) -> BytesFormat {
         match self {
             Self::Bytes(format) | Self::BytesThroughput(format) => format,
-            Self::CharsThroughput | Self::ItemsThroughput => BytesFormat::Decimal,
+            Self::Chars
+            | Self::CharsThroughput
+            | Self::Items
+            | Self::ItemsThroughput
+            | Self::Lines
+            | Self::LinesThroughput => BytesFormat::Decimal,
         }
     }
 }
@@ -178,18 +279,42 @@ impl Scale {
 
                 SUFFIXES[format as usize][self as usize]
             }
+            ScaleFormat::Chars => {
+                const SUFFIXES: &[&str; Scale::COUNT] =
+                    &["char", "Kchar", "Mchar", "Gchar", "Tchar", "Pchar"];
+
+                SUFFIXES[self as usize]
+            }
             ScaleFormat::CharsThroughput => {
                 const SUFFIXES: &[&str; Scale::COUNT] =
                     &["char/s", "Kchar/s", "Mchar/s", "Gchar/s", "Tchar/s", "Pchar/s"];
 
                 SUFFIXES[self as usize]
             }
+            ScaleFormat::Items => {
+                const SUFFIXES: &[&str; Scale::COUNT] =
+                    &["item", "Kitem", "Mitem", "Gitem", "Titem", "Pitem"];
+
+                SUFFIXES[self as usize]
+            }
             ScaleFormat::ItemsThroughput => {
                 const SUFFIXES: &[&str; Scale::COUNT] =
                     &["item/s", "Kitem/s", "Mitem/s", "Gitem/s", "Titem/s", "Pitem/s"];
 
                 SUFFIXES[self as usize]
             }
+            ScaleFormat::Lines => {
+                const SUFFIXES: &[&str; Scale::COUNT] =
+                    &["line", "Kline", "Mline", "Gline", "Tline", "Pline"];
+
+                SUFFIXES[self as usize]
+            }
+            ScaleFormat::LinesThroughput => {
+                const SUFFIXES: &[&str; Scale::COUNT] =
+                    &["line/s", "Kline/s", "Mline/s", "Gline/s", "Tline/s", "Pline/s"];
+
+                SUFFIXES[self as usize]
+            }
         }
     }
 }
@@ -198,6 +323,51 @@ impl Scale {
 mod tests {
     use super::*;
 
+    #[test]
+    fn group_thousands() {
+        #[track_caller]
+        fn test(num: &str, expected: &str) {
+            assert_eq!(super::group_thousands(num, Some(',')), expected);
+        }
+
+        test("0", "0");
+        test("999", "999");
+        test("1000", "1,000");
+        test("123456", "123,456");
+        test("1234567", "1,234,567");
+        test("1234567.25", "1,234,567.25");
+        test("", "");
+
+        assert_eq!(super::group_thousands("1234567", Some('_')), "1_234_567");
+        assert_eq!(super::group_thousands("1234567", None), "1234567");
+    }
+
+    #[test]
+    fn truncate_middle() {
+        #[track_caller]
+        fn test(s: &str, max_chars: usize, expected: &str) {
+            assert_eq!(super::truncate_middle(s, max_chars, "…"), expected);
+        }
+
+        #[track_caller]
+        fn test_ascii(s: &str, max_chars: usize, expected: &str) {
+            assert_eq!(super::truncate_middle(s, max_chars, "..."), expected);
+        }
+
+        test("abcdef", 6, "abcdef");
+        test("abcdef", 10, "abcdef");
+        test("abcdef", 5, "ab…ef");
+        test("abcdef", 4, "ab…f");
+        test("abcdef", 1, "…");
+        test("abcdef", 0, "…");
+        test("αβγδεζ", 3, "α…ζ");
+
+        test_ascii("abcdefgh", 8, "abcdefgh");
+        test_ascii("abcdefgh", 7, "ab...gh");
+        test_ascii("abcdefgh", 4, "a...");
+        test_ascii("abcdefgh", 1, "...");
+    }
+
     #[test]
     fn scale_value() {
         #[track_caller]
@@ -217,4 +387,34 @@ mod tests {
         test_decimal(1_000_000_000_000., 1., Scale::Tera);
         test_decimal(1_000_000_000_000_000., 1., Scale::Peta);
     }
+
+    #[test]
+    fn format_count() {
+        #[track_caller]
+        fn test(val: f64, counter_kind: KnownCounterKind, expected: &str) {
+            assert_eq!(super::format_count(val, 4, counter_kind, BytesFormat::Binary), expected);
+        }
+
+        test(0., KnownCounterKind::Items, "0 item");
+        test(1_500_000., KnownCounterKind::Items, "1.5 Mitem");
+        test(2_000., KnownCounterKind::Chars, "2 Kchar");
+        test(1024., KnownCounterKind::Bytes, "1 KiB");
+    }
+
+    #[test]
+    fn push_json_str() {
+        #[track_caller]
+        fn test(s: &str, expected: &str) {
+            let mut buf = String::new();
+            super::push_json_str(&mut buf, s);
+            assert_eq!(buf, expected);
+        }
+
+        test("", r#""""#);
+        test("math::add", r#""math::add""#);
+        test("Vec<&str>", r#""Vec<&str>""#);
+        test(r#"say "hi""#, r#""say \"hi\"""#);
+        test(r"C:\benches", r#""C:\\benches""#);
+        test("a\tb\nc\u{1}", r#""a\tb\nc\u0001""#);
+    }
 }
@@ -1,8 +1,11 @@
 use std::{
     any::{Any, TypeId},
+    io::IsTerminal,
     num::NonZeroUsize,
     ops::{Deref, DerefMut},
+    path::PathBuf,
     sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    time::{Duration, Instant},
 };
 
 pub mod fmt;
@@ -101,6 +104,147 @@ pub(crate) fn known_parallelism() -> NonZeroUsize {
     }
 }
 
+/// Returns the CPU that the current thread is running on.
+///
+/// This is only supported on Linux and returns `None` elsewhere.
+#[inline]
+pub(crate) fn current_cpu() -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: `sched_getcpu` has no preconditions.
+        let cpu = unsafe { libc::sched_getcpu() };
+        u32::try_from(cpu).ok()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    None
+}
+
+/// Pins the current thread to the `index`-th CPU that the process may run on,
+/// wrapping around if there are fewer CPUs.
+///
+/// This is only supported on Linux and does nothing elsewhere.
+pub(crate) fn pin_current_thread(index: usize) {
+    #[cfg(target_os = "linux")]
+    // SAFETY: `cpu_set_t` is plain data, and the calls only access it.
+    unsafe {
+        let set_size = std::mem::size_of::<libc::cpu_set_t>();
+
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, set_size, &mut set) != 0 {
+            return;
+        }
+
+        let cpus: Vec<usize> =
+            (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect();
+
+        let Some(&cpu) = cpus.get(index % cpus.len().max(1)) else {
+            return;
+        };
+
+        libc::CPU_ZERO(&mut set);
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, set_size, &set);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = index;
+}
+
+/// Returns the total user and system CPU time of child processes that have
+/// terminated and been waited on.
+///
+/// This is only supported on Unix and returns `None` elsewhere.
+pub(crate) fn children_cpu_time() -> Option<Duration> {
+    #[cfg(unix)]
+    // SAFETY: `rusage` is plain data, and `getrusage` only writes to it.
+    unsafe {
+        let mut usage: libc::rusage = std::mem::zeroed();
+        if libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage) != 0 {
+            return None;
+        }
+
+        let to_duration =
+            |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1_000);
+
+        Some(to_duration(usage.ru_utime) + to_duration(usage.ru_stime))
+    }
+
+    #[cfg(not(unix))]
+    None
+}
+
+/// Keeps the current CPU busy for `duration`, which brings it out of
+/// low-power states.
+pub(crate) fn spin(duration: Duration) {
+    let start = Instant::now();
+    let mut counter = 0u64;
+
+    while start.elapsed() < duration {
+        // Do real work rather than `spin_loop`, which may lower power usage.
+        for _ in 0..1_000 {
+            counter = crate::black_box(counter.wrapping_add(1));
+        }
+    }
+}
+
+/// Returns the path of a file named `suffix` after the benchmark executable.
+///
+/// Files stored next to the executable belong to that benchmark binary alone,
+/// and `cargo clean` removes them. If `share_features` is set, Cargo's hash
+/// suffix is excluded from the name so that builds of the same benchmark with
+/// different cargo features share the file.
+pub(crate) fn exe_data_path(suffix: &str, share_features: bool) -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+
+    let stem = exe.file_stem()?.to_str()?;
+    let mut file_name = if share_features { strip_cargo_hash(stem) } else { stem }.to_owned();
+    file_name.push_str(suffix);
+
+    Some(exe.with_file_name(file_name))
+}
+
+/// Removes the `-<hash>` that Cargo appends to executable names.
+fn strip_cargo_hash(exe_name: &str) -> &str {
+    match exe_name.rsplit_once('-') {
+        Some((stem, hash)) if hash.len() == 16 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            stem
+        }
+        _ => exe_name,
+    }
+}
+
+/// Returns the number of columns of the terminal that stdout is written to.
+///
+/// The `COLUMNS` environment variable takes priority, so that the width can
+/// be set when stdout is not a terminal.
+pub(crate) fn terminal_width() -> Option<usize> {
+    let columns = std::env::var("COLUMNS").ok().and_then(|columns| columns.parse().ok());
+    if let Some(columns) = columns.filter(|&columns: &usize| columns > 0) {
+        return Some(columns);
+    }
+
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        // SAFETY: `winsize` is plain data, and `TIOCGWINSZ` writes into it.
+        let size = unsafe {
+            let mut size: libc::winsize = std::mem::zeroed();
+            let result = libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size);
+            (result == 0).then_some(size)
+        };
+
+        if let Some(size) = size.filter(|size| size.ws_col > 0) {
+            return Some(size.ws_col.into());
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use crate::black_box;
@@ -113,6 +257,38 @@ mod tests {
         assert_eq!(black_box(f)(), black_box(f)());
     }
 
+    /// Pinning restricts the thread to one allowed CPU, wrapping around by
+    /// index.
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn pin_current_thread() {
+        fn allowed_cpus() -> Vec<usize> {
+            // SAFETY: `cpu_set_t` is plain data, and the calls only access it.
+            unsafe {
+                let mut set: libc::cpu_set_t = std::mem::zeroed();
+                let set_size = std::mem::size_of::<libc::cpu_set_t>();
+                assert_eq!(libc::sched_getaffinity(0, set_size, &mut set), 0);
+
+                (0..libc::CPU_SETSIZE as usize).filter(|&cpu| libc::CPU_ISSET(cpu, &set)).collect()
+            }
+        }
+
+        let cpus = allowed_cpus();
+
+        for index in [0, 1, cpus.len()] {
+            // Pin a new thread so that the test thread stays unpinned.
+            let pinned = std::thread::spawn(move || {
+                super::pin_current_thread(index);
+                (allowed_cpus(), current_cpu())
+            })
+            .join()
+            .unwrap();
+
+            let cpu = cpus[index % cpus.len()];
+            assert_eq!(pinned, (vec![cpu], Some(cpu as u32)));
+        }
+    }
+
     #[test]
     fn slice_middle() {
         use super::slice_middle;
@@ -125,4 +301,12 @@ mod tests {
         assert_eq!(slice_middle(&[1, 2, 3, 4]), &[2, 3]);
         assert_eq!(slice_middle(&[1, 2, 3, 4, 5]), &[3]);
     }
+
+    #[test]
+    fn strip_cargo_hash() {
+        assert_eq!(super::strip_cargo_hash("atomic-8eb745c4ea118f7a"), "atomic");
+        assert_eq!(super::strip_cargo_hash("my-bench-8eb745c4ea118f7a"), "my-bench");
+        assert_eq!(super::strip_cargo_hash("my-bench"), "my-bench");
+        assert_eq!(super::strip_cargo_hash("atomic"), "atomic");
+    }
 }
@@ -0,0 +1,60 @@
+// Tests that `Divan::configure_entries` adjusts options per entry.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+use divan::Divan;
+
+static IO_ITERS: AtomicUsize = AtomicUsize::new(0);
+static OTHER_ITERS: AtomicUsize = AtomicUsize::new(0);
+static IGNORED_ITERS: AtomicUsize = AtomicUsize::new(0);
+
+#[divan::bench_group(sample_count = 10, sample_size = 1)]
+mod io {
+    use super::*;
+
+    // 7 × 3 = 21
+    #[divan::bench]
+    fn bench() {
+        IO_ITERS.fetch_add(1, SeqCst);
+    }
+}
+
+// 10 × 1 = 10
+#[divan::bench(sample_count = 10, sample_size = 1)]
+fn other() {
+    OTHER_ITERS.fetch_add(1, SeqCst);
+}
+
+#[divan::bench(sample_count = 10, sample_size = 1)]
+fn ignored() {
+    IGNORED_ITERS.fetch_add(1, SeqCst);
+}
+
+#[test]
+fn configure_entries() {
+    Divan::default()
+        .configure_entries(|entry, options| {
+            if entry.module_path().ends_with("::io") {
+                // Overrides attribute options.
+                options.sample_count(7).sample_size(3);
+            } else if entry.name() == "ignored" {
+                options.ignore(true);
+            }
+        })
+        .run_benches();
+
+    assert_eq!(IO_ITERS.load(SeqCst), 21);
+    assert_eq!(OTHER_ITERS.load(SeqCst), 10);
+    assert_eq!(IGNORED_ITERS.load(SeqCst), 0);
+}
+
+/// Tests that boxed callbacks do not make `Divan` `!Send` or `!Sync`.
+#[test]
+fn divan_is_send_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Divan>();
+}
@@ -3,7 +3,7 @@
 // Miri cannot discover benchmarks.
 #![cfg(not(miri))]
 
-use divan::__private::{EntryMeta, BENCH_ENTRIES, GROUP_ENTRIES};
+use divan::__private::{BenchEntryRunner, EntryMeta, BENCH_ENTRIES, GROUP_ENTRIES};
 
 #[divan::bench]
 fn outer() {}
@@ -118,3 +118,56 @@ fn ignore() {
     assert!(!get_ignore(find_outer()));
     assert!(!get_ignore(find_outer_group()));
 }
+
+const TICKET: &str = "PERF-42";
+
+#[divan::bench(meta("team" = "storage", "ticket" = TICKET))]
+fn with_meta() {}
+
+#[divan::bench_group(meta("team" = "storage"))]
+mod group_with_meta {}
+
+#[test]
+fn custom_meta() {
+    assert_eq!(
+        find_meta!(BENCH_ENTRIES, "with_meta").custom_meta,
+        [("team", "storage"), ("ticket", "PERF-42")]
+    );
+    assert_eq!(find_meta!(GROUP_ENTRIES, "group_with_meta").custom_meta, [("team", "storage")]);
+
+    assert!(find_outer().custom_meta.is_empty());
+    assert!(find_outer_group().custom_meta.is_empty());
+}
+
+#[divan::bench(args = [1, 64, 4096])]
+fn with_args(n: usize) -> usize {
+    n
+}
+
+#[test]
+fn args() {
+    fn find_runner(raw_name: &str) -> &'static BenchEntryRunner {
+        let entry = BENCH_ENTRIES.iter().find(|entry| entry.meta.raw_name == raw_name);
+        &entry.expect("entry not found").bench
+    }
+
+    assert!(matches!(find_runner("with_args"), BenchEntryRunner::Args(_)));
+    assert!(matches!(find_runner("outer"), BenchEntryRunner::Plain(_)));
+
+    // Each argument is benchmarked as a child named after it.
+    static ARG_PATHS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+    // Skip benchmarks that Divan registers in its own crate.
+    divan::Divan::default()
+        .skip_regex("^divan::")
+        .sample_count(1)
+        .sample_size(1)
+        .on_samples(|samples| {
+            if let Some(arg) = samples.path().strip_prefix("entry_properties::with_args::") {
+                ARG_PATHS.lock().unwrap().push(arg.to_owned());
+            }
+        })
+        .run_benches();
+
+    assert_eq!(*ARG_PATHS.lock().unwrap(), ["1", "64", "4096"]);
+}
@@ -0,0 +1,43 @@
+// Tests that `Divan::format` selects the output format without CLI arguments.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::process::Command;
+
+use divan::{Divan, OutputFormat};
+
+/// Set in the child process that runs the benchmarks, so that the parent can
+/// capture its standard output.
+const CHILD_ENV: &str = "DIVAN_TEST_FORMAT_CHILD";
+
+#[divan::bench(sample_count = 1, sample_size = 1)]
+fn bench() {}
+
+#[test]
+fn terse() {
+    if std::env::var_os(CHILD_ENV).is_some() {
+        // Skip benchmarks that Divan registers in its own crate.
+        Divan::default().skip_regex("^divan::").format(OutputFormat::Terse).run_benches();
+        return;
+    }
+
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["terse", "--exact", "--nocapture", "--test-threads", "1"])
+        .env(CHILD_ENV, "1")
+        .env_remove("DIVAN_FORMAT")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Lines may start with libtest's progress, such as `test terse ... `.
+    let benches: Vec<&str> =
+        stdout.lines().filter_map(|line| line.find("format::").map(|i| &line[i..])).collect();
+
+    assert_eq!(benches.len(), 1, "{stdout}");
+    assert!(benches[0].starts_with("format::bench\t"), "{stdout}");
+
+    // Pretty output draws a tree.
+    assert!(!stdout.contains('╰'), "{stdout}");
+}
@@ -0,0 +1,61 @@
+// Tests that `--jobs` measures benchmarks at the same time while still
+// reporting results in tree order.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering::SeqCst},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use divan::Divan;
+
+static STARTED: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+static OVERLAPPED: [AtomicBool; 2] = [AtomicBool::new(false), AtomicBool::new(false)];
+static REPORTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Waits for the other benchmark to start, which never happens if benchmarks
+/// are measured one at a time.
+fn rendezvous(index: usize) {
+    STARTED[index].store(true, SeqCst);
+
+    if OVERLAPPED[index].load(SeqCst) {
+        return;
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while Instant::now() < deadline {
+        if STARTED[1 - index].load(SeqCst) {
+            OVERLAPPED[index].store(true, SeqCst);
+            return;
+        }
+        std::hint::spin_loop();
+    }
+}
+
+#[divan::bench(sample_count = 1, sample_size = 1)]
+fn a() {
+    rendezvous(0);
+}
+
+#[divan::bench(sample_count = 1, sample_size = 1)]
+fn b() {
+    rendezvous(1);
+}
+
+#[test]
+fn concurrent() {
+    // Skip benchmarks that Divan registers in its own crate.
+    Divan::default()
+        .skip_regex("^divan::")
+        .jobs(2)
+        .on_samples(|samples| REPORTED.lock().unwrap().push(samples.path().to_owned()))
+        .run_benches();
+
+    assert!(OVERLAPPED.iter().all(|overlapped| overlapped.load(SeqCst)));
+    assert_eq!(*REPORTED.lock().unwrap(), ["jobs::a", "jobs::b"]);
+}
@@ -0,0 +1,46 @@
+// Tests that the `priority` option changes the order in which benchmarks are
+// measured but not the order in which results are reported.
+
+// Miri cannot discover benchmarks.
+#![cfg(not(miri))]
+
+use std::sync::Mutex;
+
+use divan::Divan;
+
+static MEASURED: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+static REPORTED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn measure(name: &'static str) {
+    let mut measured = MEASURED.lock().unwrap();
+    if !measured.contains(&name) {
+        measured.push(name);
+    }
+}
+
+#[divan::bench(sample_count = 1, sample_size = 1)]
+fn a() {
+    measure("a");
+}
+
+#[divan::bench(sample_count = 1, sample_size = 1, priority = 1)]
+fn b() {
+    measure("b");
+}
+
+#[divan::bench(sample_count = 1, sample_size = 1, priority = 2)]
+fn c() {
+    measure("c");
+}
+
+#[test]
+fn priority_order() {
+    // Skip benchmarks that Divan registers in its own crate.
+    Divan::default()
+        .skip_regex("^divan::")
+        .on_samples(|samples| REPORTED.lock().unwrap().push(samples.path().to_owned()))
+        .run_benches();
+
+    assert_eq!(*MEASURED.lock().unwrap(), ["c", "b", "a"]);
+    assert_eq!(*REPORTED.lock().unwrap(), ["priority::a", "priority::b", "priority::c"]);
+}
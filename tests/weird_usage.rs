@@ -5,7 +5,7 @@
 
 use std::time::Duration;
 
-use divan::{Divan, __private::BENCH_ENTRIES};
+use divan::{__private::BENCH_ENTRIES, Divan};
 
 #[divan::bench(bytes_count = 0u8, chars_count = 0u16, items_count = 0u32)]
 fn zero_throughput() {}
@@ -69,6 +69,14 @@ fn bench_consts<const N: isize>() {}
 #[divan::bench(args = [])]
 fn empty_args(_: usize) {}
 
+#[divan::bench(args = [1, 2], counter = arg)]
+fn arg_counter(_: usize) {}
+
+#[divan::bench(counter = arg, args = [&1u8, &2])]
+fn arg_counter_bencher(bencher: divan::Bencher, _: &u8) {
+    bencher.bench(|| {});
+}
+
 #[divan::bench(types = [])]
 #[allow(dead_code)]
 fn empty_types<T>() {}
@@ -133,3 +141,35 @@ fn path() {
         }
     }
 }
+
+// Test that `counter = arg` counts each argument as items.
+#[test]
+fn arg_counter_counts() {
+    use divan::counter::ItemsCount;
+    use std::sync::Mutex;
+
+    static COUNTS: Mutex<Vec<(String, Vec<u64>)>> = Mutex::new(Vec::new());
+
+    Divan::default()
+        .filter_file_contents("^weird_usage::arg_counter")
+        .sample_count(2)
+        .sample_size(1)
+        .on_samples(|samples| {
+            let counts = samples.counts::<ItemsCount>().unwrap_or_default().to_vec();
+            COUNTS.lock().unwrap().push((samples.path().to_owned(), counts));
+        })
+        .run_benches();
+
+    let mut counts = COUNTS.lock().unwrap().clone();
+    counts.sort();
+
+    assert_eq!(
+        counts,
+        [
+            ("weird_usage::arg_counter::1".to_owned(), vec![1, 1]),
+            ("weird_usage::arg_counter::2".to_owned(), vec![2, 2]),
+            ("weird_usage::arg_counter_bencher::1".to_owned(), vec![1, 1]),
+            ("weird_usage::arg_counter_bencher::2".to_owned(), vec![2, 2]),
+        ]
+    );
+}